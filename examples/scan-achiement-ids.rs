@@ -1,4 +1,5 @@
 use ps2api::events::{self, api_command::Subscribe};
+use ps2api::shared::ids::AchievementId;
 use std::collections::HashSet;
 use std::fs;
 use std::io::{Read, Write};
@@ -8,7 +9,7 @@ use std::time::SystemTime;
 
 #[tokio::main]
 async fn main() {
-    let mut achievement_ids: HashSet<u64> = HashSet::new();
+    let mut achievement_ids: HashSet<AchievementId> = HashSet::new();
 
     let mut fs_opts = fs::OpenOptions::new();
     fs_opts.read(true);
@@ -30,7 +31,7 @@ async fn main() {
                     break;
                 }
                 Some(id_str) => {
-                    let id = id_str.parse::<u64>();
+                    let id = id_str.parse::<AchievementId>();
                     if id.is_err() {
                         break;
                     }
@@ -76,7 +77,7 @@ async fn main() {
                             + &a.achievement_id.to_string();
                         println!("{}", msg);
                         achievement_ids.insert(a.achievement_id);
-                        file.write((a.achievement_id.to_string() + "\n").as_bytes());
+                        let _ = file.write_all((a.achievement_id.to_string() + "\n").as_bytes());
                     }
                 }
             }
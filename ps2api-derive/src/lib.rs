@@ -0,0 +1,477 @@
+// project linter rules:
+#![allow(clippy::needless_return)] // returning if your missing a semicolon is a very bad idea
+#![allow(clippy::needless_late_init)] // let statment with an initializer is significantly less readable
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::{
+    bracketed, parse_macro_input, punctuated::Punctuated, spanned::Spanned, token, Data,
+    DeriveInput, Fields, GenericArgument, Ident, LitStr, Path, PathArguments, Token, Type,
+};
+
+///
+/// Derives the boilerplate of a census entity model
+///
+/// Struct attributes:
+/// - `#[census(collection = "character", resolves = CharacterResolves)]`
+///
+/// Field attributes:
+/// - `#[census(client)]` the `Arc<RestClient>` the entity was created with
+/// - `#[census(id = "character_id")]` the id of the entity and the census field it is searched by
+/// - `#[census(path = "times.last_login")]` a `CensusValue` read from the given json path
+/// - `#[census(path = "outfit.alias", resolve = Outfit)]` a `CensusValue` that is only returned with a resolve
+/// - `#[census(path = "stats", resolve = [Stat, StatByFaction])]` a `CensusValue` that is built from several resolves
+///
+/// Fields without an attribute are initialised with `Default::default()` and left to the entity to update.
+///
+/// Generates `new`, `update`, `from_json_value`, `resolves_for`, `projection`, the `COLLECTION` and
+/// `ID_FIELD` constants, the `<Entity>Field` and `<Entity>Change` enums and an implementation of `RegistryEntity`.
+/// The private `resolves_query` and `fetch_resolves_json` build and run the query for an entity by id.
+/// The generated code refers to `crate::rest` and is only meant to be used inside ps2api.
+///
+#[proc_macro_derive(CensusEntity, attributes(census))]
+pub fn derive_census_entity(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match expand(input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+enum FieldKind {
+    Client,
+    Id(LitStr),
+    Value {
+        path: LitStr,
+        resolves: Vec<Ident>,
+        inner: Box<Type>,
+    },
+    Other,
+}
+
+struct EntityField {
+    ident: Ident,
+    ty: Type,
+    kind: FieldKind,
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let entity = &input.ident;
+    let vis = &input.vis;
+
+    let mut collection: Option<LitStr> = None;
+    let mut resolves: Option<Path> = None;
+
+    for attr in &input.attrs {
+        if !attr.path().is_ident("census") {
+            continue;
+        }
+
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("collection") {
+                collection = Some(meta.value()?.parse()?);
+                return Ok(());
+            }
+            if meta.path.is_ident("resolves") {
+                resolves = Some(meta.value()?.parse()?);
+                return Ok(());
+            }
+            return Err(meta.error("unknown census attribute"));
+        })?;
+    }
+
+    let collection = match collection {
+        Some(c) => c,
+        None => {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "missing #[census(collection = \"...\")]",
+            ));
+        }
+    };
+
+    let resolves = match resolves {
+        Some(r) => r,
+        None => {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "missing #[census(resolves = ...)]",
+            ));
+        }
+    };
+
+    let named = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(named) => &named.named,
+            _ => {
+                return Err(syn::Error::new(
+                    input.span(),
+                    "CensusEntity requires named fields",
+                ));
+            }
+        },
+        _ => {
+            return Err(syn::Error::new(
+                input.span(),
+                "CensusEntity can only be derived for structs",
+            ));
+        }
+    };
+
+    let mut fields: Vec<EntityField> = Vec::with_capacity(named.len());
+
+    for field in named {
+        let ident = field.ident.clone().unwrap();
+        let mut kind = FieldKind::Other;
+
+        for attr in &field.attrs {
+            if !attr.path().is_ident("census") {
+                continue;
+            }
+
+            let mut path: Option<LitStr> = None;
+            let mut field_resolves: Vec<Ident> = Vec::new();
+
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("client") {
+                    kind = FieldKind::Client;
+                    return Ok(());
+                }
+                if meta.path.is_ident("id") {
+                    kind = FieldKind::Id(meta.value()?.parse()?);
+                    return Ok(());
+                }
+                if meta.path.is_ident("path") {
+                    path = Some(meta.value()?.parse()?);
+                    return Ok(());
+                }
+                if meta.path.is_ident("resolve") {
+                    let value = meta.value()?;
+
+                    if value.peek(token::Bracket) {
+                        let list;
+                        bracketed!(list in value);
+                        field_resolves
+                            .extend(Punctuated::<Ident, Token![,]>::parse_terminated(&list)?);
+                    } else {
+                        field_resolves.push(value.parse()?);
+                    }
+
+                    return Ok(());
+                }
+                return Err(meta.error("unknown census attribute"));
+            })?;
+
+            if let Some(path) = path {
+                kind = FieldKind::Value {
+                    path,
+                    resolves: field_resolves,
+                    inner: Box::new(census_value_inner(&field.ty)?),
+                };
+            }
+        }
+
+        fields.push(EntityField {
+            ident,
+            ty: field.ty.clone(),
+            kind,
+        });
+    }
+
+    if !fields.iter().any(|f| matches!(f.kind, FieldKind::Client)) {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "missing #[census(client)] field",
+        ));
+    }
+
+    let id_field = fields
+        .iter()
+        .find(|f| matches!(f.kind, FieldKind::Id(_)))
+        .ok_or_else(|| {
+            syn::Error::new(Span::call_site(), "missing #[census(id = \"...\")] field")
+        })?;
+
+    let id_ident = &id_field.ident;
+    let id_ty = &id_field.ty;
+    let id_name = match &id_field.kind {
+        FieldKind::Id(name) => name,
+        _ => unreachable!(),
+    };
+
+    let field_enum = format_ident!("{}Field", entity);
+    let change_enum = format_ident!("{}Change", entity);
+
+    let mut new_inits: Vec<TokenStream2> = Vec::with_capacity(fields.len());
+    let mut updates: Vec<TokenStream2> = Vec::new();
+    let mut field_variants: Vec<TokenStream2> = Vec::new();
+    let mut change_variants: Vec<TokenStream2> = Vec::new();
+    let mut change_fields: Vec<TokenStream2> = Vec::new();
+    let mut resolve_arms: Vec<TokenStream2> = Vec::new();
+    let mut projection_arms: Vec<TokenStream2> = Vec::new();
+
+    for field in &fields {
+        let ident = &field.ident;
+
+        match &field.kind {
+            FieldKind::Client => {
+                new_inits.push(quote! { #ident: rest_client });
+            }
+            FieldKind::Id(_) => {
+                new_inits.push(quote! { #ident: id });
+            }
+            FieldKind::Other => {
+                new_inits.push(quote! { #ident: ::std::default::Default::default() });
+            }
+            FieldKind::Value {
+                path,
+                resolves: field_resolves,
+                inner,
+            } => {
+                let variant = format_ident!("{}", camel_case(&ident.to_string()));
+                let segments: Vec<String> =
+                    path.value().split('.').map(|s| s.to_string()).collect();
+                let top_level = &segments[0];
+
+                new_inits.push(quote! {
+                    #ident: crate::rest::census_value::CensusValue {
+                        value: None,
+                        last_updated: None,
+                    }
+                });
+
+                updates.push(quote! {
+                    if let Some(change) = self.#ident.update(&json #([#segments])*).change() {
+                        changes.push(#change_enum::#variant(change));
+                    }
+                });
+
+                let doc = format!("`{}`", path.value());
+                field_variants.push(quote! {
+                    #[doc = #doc]
+                    #variant
+                });
+                change_variants
+                    .push(quote! { #variant(crate::rest::census_value::ValueChange<#inner>) });
+                change_fields.push(quote! { #change_enum::#variant(_) => #field_enum::#variant });
+
+                match field_resolves.is_empty() {
+                    false => {
+                        resolve_arms.push(quote! {
+                            #field_enum::#variant => {
+                                #(
+                                    if !resolves.contains(&#resolves::#field_resolves) {
+                                        resolves.push(#resolves::#field_resolves);
+                                    }
+                                )*
+                            }
+                        });
+                    }
+                    true => {
+                        projection_arms.push(quote! {
+                            #field_enum::#variant => {
+                                if !projection.contains(&#top_level) {
+                                    projection.push(#top_level);
+                                }
+                            }
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    let list_key = format!("{}_list", collection.value());
+
+    let field_doc = format!("A field of a {} that is read from census", entity);
+    let change_doc = format!("A field of a {} that changed during an update", entity);
+
+    return Ok(quote! {
+        #[doc = #field_doc]
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        #vis enum #field_enum {
+            #(#field_variants,)*
+        }
+
+        #[doc = #change_doc]
+        #[derive(Clone, Debug, PartialEq)]
+        #vis enum #change_enum {
+            #(#change_variants,)*
+        }
+
+        impl #change_enum {
+            /// The field that changed
+            pub fn field(&self) -> #field_enum {
+                match self {
+                    #(#change_fields,)*
+                }
+            }
+        }
+
+        impl #entity {
+            /// The census collection this entity is fetched from
+            pub const COLLECTION: &'static str = #collection;
+            /// The census field the entity id is searched by
+            pub const ID_FIELD: &'static str = #id_name;
+
+            /// Creates an entity without fetching any of its fields
+            pub fn new(id: #id_ty, rest_client: ::std::sync::Arc<crate::rest::RestClient>) -> Self {
+                #entity {
+                    #(#new_inits,)*
+                }
+            }
+
+            /// Updates every census field from a fetched json object, returning the fields that changed
+            fn update(&mut self, json: &::serde_json::Value) -> Vec<#change_enum> {
+                let mut changes = Vec::new();
+
+                #(#updates)*
+
+                return changes;
+            }
+
+            /// Creates an entity from an already fetched json object, fails if it has no id
+            pub fn from_json_value(
+                json: &::serde_json::Value,
+                rest_client: ::std::sync::Arc<crate::rest::RestClient>,
+            ) -> Result<Self, crate::utils::CensusError> {
+                let id = crate::rest::registry::parse_entity_id::<Self>(json)?;
+
+                let mut entity = #entity::new(id, rest_client);
+
+                entity.update(json);
+
+                return Ok(entity);
+            }
+
+            /// The query for the entity with the given id and resolves
+            #[allow(dead_code)]
+            fn resolves_query(
+                rest_client: &crate::rest::RestClient,
+                id: &#id_ty,
+                resolves: Option<Vec<#resolves>>,
+            ) -> crate::rest::query_builder::QueryBuilder {
+                let mut query = rest_client.get_query_builder(#collection);
+
+                if let Some(resolves) = resolves {
+                    for r in resolves {
+                        query.resolve(&crate::rest::query_builder::Resolveable::to_resolve_string(&r));
+                    }
+                }
+
+                query.limit(1);
+
+                query.search(#id_name.to_string(), id.to_string());
+
+                return query;
+            }
+
+            /// The json object of the entity with the given resolves, null if census does not know it
+            #[allow(dead_code)]
+            async fn fetch_resolves_json(
+                rest_client: &crate::rest::RestClient,
+                id: &#id_ty,
+                resolves: Option<Vec<#resolves>>,
+            ) -> Result<::serde_json::Value, crate::utils::CensusError> {
+                let query = #entity::resolves_query(rest_client, id, resolves);
+
+                let mut jsonval = query.get().await?;
+
+                return Ok(jsonval[#list_key][0].take());
+            }
+
+            /// The resolves needed to populate the given fields
+            pub fn resolves_for(fields: &[#field_enum]) -> Vec<#resolves> {
+                let mut resolves: Vec<#resolves> = Vec::new();
+
+                for field in fields {
+                    #[allow(unreachable_patterns)]
+                    match field {
+                        #(#resolve_arms)*
+                        _ => {}
+                    }
+                }
+
+                return resolves;
+            }
+
+            /// The top level json keys that have to be shown to populate the given fields,
+            /// fields provided by a resolve are added by the resolve itself
+            pub fn projection(fields: &[#field_enum]) -> Vec<&'static str> {
+                let mut projection: Vec<&'static str> = vec![#id_name];
+
+                for field in fields {
+                    #[allow(unreachable_patterns)]
+                    match field {
+                        #(#projection_arms)*
+                        _ => {}
+                    }
+                }
+
+                return projection;
+            }
+        }
+
+        impl crate::rest::registry::RegistryEntity for #entity {
+            type Id = #id_ty;
+            type Change = #change_enum;
+
+            const COLLECTION: &'static str = #collection;
+            const ID_FIELD: &'static str = #id_name;
+
+            fn entity_id(&self) -> &#id_ty {
+                return &self.#id_ident;
+            }
+
+            fn new_entity(id: #id_ty, rest_client: ::std::sync::Arc<crate::rest::RestClient>) -> Self {
+                return #entity::new(id, rest_client);
+            }
+
+            fn update_entity(&mut self, json: &::serde_json::Value) -> Vec<#change_enum> {
+                return self.update(json);
+            }
+        }
+    });
+}
+
+fn census_value_inner(ty: &Type) -> syn::Result<Type> {
+    if let Type::Path(type_path) = ty {
+        if let Some(segment) = type_path.path.segments.last() {
+            if segment.ident == "CensusValue" {
+                if let PathArguments::AngleBracketed(args) = &segment.arguments {
+                    if let Some(GenericArgument::Type(inner)) = args.args.first() {
+                        return Ok(inner.clone());
+                    }
+                }
+            }
+        }
+    }
+
+    return Err(syn::Error::new(
+        ty.span(),
+        "census path fields must be a CensusValue<T>",
+    ));
+}
+
+fn camel_case(name: &str) -> String {
+    let mut out = String::with_capacity(name.len());
+    let mut upper = true;
+
+    for c in name.chars() {
+        if c == '_' {
+            upper = true;
+            continue;
+        }
+
+        if upper {
+            out.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            out.push(c);
+        }
+    }
+
+    return out;
+}
@@ -1,6 +1,10 @@
 use serde_json::Value;
 
 use crate::events::api_events::parse_helpers::*;
+use crate::shared::ids::{
+    AchievementId, CharacterId, ExperienceId, FacilityId, ItemId, LoadoutId, OutfitId, VehicleId,
+    WorldId, ZoneId,
+};
 use crate::utils::CensusError;
 
 pub mod event_types;
@@ -21,8 +25,8 @@ pub struct ConnectionStateChange {
 // Player Connect/Disconnect Events
 #[derive(Debug)]
 pub struct PlayerLogin {
-    pub character_id: String,
-    pub world_id: u8,
+    pub character_id: CharacterId,
+    pub world_id: WorldId,
     pub timestamp: String,
 }
 
@@ -38,8 +42,8 @@ impl Event for PlayerLogin {
 
 #[derive(Debug)]
 pub struct PlayerLogout {
-    pub character_id: String,
-    pub world_id: u8,
+    pub character_id: CharacterId,
+    pub world_id: WorldId,
     pub timestamp: String,
 }
 
@@ -57,8 +61,8 @@ impl Event for PlayerLogout {
 #[derive(Debug)]
 pub struct ContinentLock {
     pub timestamp: String,
-    pub world_id: u8,
-    pub zone_id: u32,
+    pub world_id: WorldId,
+    pub zone_id: ZoneId,
     pub triggering_faction: String,
     pub previous_faction: String,
     pub vs_population: String,
@@ -88,8 +92,8 @@ impl Event for ContinentLock {
 #[derive(Debug)]
 pub struct ContinentUnlock {
     pub timestamp: String,
-    pub world_id: u8,
-    pub zone_id: u32,
+    pub world_id: WorldId,
+    pub zone_id: ZoneId,
     pub triggering_faction: String,
     pub previous_faction: String,
     pub vs_population: String,
@@ -119,12 +123,12 @@ impl Event for ContinentUnlock {
 #[derive(Debug)]
 pub struct FacilityControl {
     pub timestamp: String,
-    pub world_id: u8,
-    pub zone_id: u32,
-    pub facility_id: String,
+    pub world_id: WorldId,
+    pub zone_id: ZoneId,
+    pub facility_id: FacilityId,
     pub new_faction_id: u8,
     pub old_faction_id: u8,
-    pub outfit_id: String,
+    pub outfit_id: OutfitId,
     pub duration_held: String,
 }
 
@@ -134,10 +138,10 @@ impl Event for FacilityControl {
             timestamp: parse_timestamp(json)?,
             world_id: parse_world_id(json)?,
             zone_id: parse_zone_id(json)?,
-            facility_id: parse_string("facility_id", json)?,
+            facility_id: try_parse_to("facility_id", json)?,
             new_faction_id: try_parse_to("new_faction_id", json)?,
             old_faction_id: try_parse_to("old_faction_id", json)?,
-            outfit_id: parse_string("outfit_id", json)?,
+            outfit_id: try_parse_to("outfit_id", json)?,
             duration_held: parse_string("duration_held", json)?,
         });
     }
@@ -146,8 +150,8 @@ impl Event for FacilityControl {
 #[derive(Debug)]
 pub struct MetagameEvent {
     pub timestamp: String,
-    pub world_id: u8,
-    pub zone_id: u32,
+    pub world_id: WorldId,
+    pub zone_id: ZoneId,
     pub experience_bonus: String,
     pub faction_nc: String,
     pub faction_tr: String,
@@ -174,11 +178,11 @@ impl Event for MetagameEvent {
 
 #[derive(Debug)]
 pub struct AchievementEarned {
-    pub character_id: String,
+    pub character_id: CharacterId,
     pub timestamp: String,
-    pub world_id: u8,
-    pub zone_id: u32,
-    pub achievement_id: u64,
+    pub world_id: WorldId,
+    pub zone_id: ZoneId,
+    pub achievement_id: AchievementId,
 }
 
 impl Event for AchievementEarned {
@@ -195,10 +199,10 @@ impl Event for AchievementEarned {
 
 #[derive(Debug)]
 pub struct BattleRankUp {
-    pub character_id: String,
+    pub character_id: CharacterId,
     pub timestamp: String,
-    pub world_id: u8,
-    pub zone_id: u32,
+    pub world_id: WorldId,
+    pub zone_id: ZoneId,
     pub battle_rank: u8,
 }
 
@@ -216,33 +220,33 @@ impl Event for BattleRankUp {
 
 #[derive(Debug)]
 pub struct Death {
-    pub attacker_character_id: String,
+    pub attacker_character_id: CharacterId,
     pub attacker_fire_mode_id: String,
-    pub attacker_loadout_id: String,
-    pub attacker_vehicle_id: String,
-    pub attacker_weapon_id: String,
-    pub character_id: String,
-    pub character_loadout_id: String,
+    pub attacker_loadout_id: LoadoutId,
+    pub attacker_vehicle_id: VehicleId,
+    pub attacker_weapon_id: ItemId,
+    pub character_id: CharacterId,
+    pub character_loadout_id: LoadoutId,
     pub is_headshot: bool,
     pub timestamp: String,
-    pub vehicle_id: String,
-    pub world_id: u8,
-    pub zone_id: u32,
+    pub vehicle_id: VehicleId,
+    pub world_id: WorldId,
+    pub zone_id: ZoneId,
 }
 
 impl Event for Death {
     fn from_json(json: &Value) -> Result<Self, CensusError> {
         return Ok(Self {
-            attacker_character_id: parse_string("attacker_character_id", json)?,
+            attacker_character_id: try_parse_to("attacker_character_id", json)?,
             attacker_fire_mode_id: parse_string("attacker_fire_mode_id", json)?,
-            attacker_loadout_id: parse_string("attacker_loadout_id", json)?,
-            attacker_vehicle_id: parse_string("attacker_vehicle_id", json)?,
-            attacker_weapon_id: parse_string("attacker_weapon_id", json)?,
+            attacker_loadout_id: try_parse_to("attacker_loadout_id", json)?,
+            attacker_vehicle_id: try_parse_to("attacker_vehicle_id", json)?,
+            attacker_weapon_id: try_parse_to("attacker_weapon_id", json)?,
             character_id: parse_character_id(json)?,
-            character_loadout_id: parse_string("character_loadout_id", json)?,
+            character_loadout_id: try_parse_to("character_loadout_id", json)?,
             is_headshot: parse_bool_from_numstr("is_headshot", json)?,
             timestamp: parse_timestamp(json)?,
-            vehicle_id: parse_string("vehicle_id", json)?,
+            vehicle_id: try_parse_to("vehicle_id", json)?,
             world_id: parse_world_id(json)?,
             zone_id: parse_zone_id(json)?,
         });
@@ -251,13 +255,13 @@ impl Event for Death {
 
 #[derive(Debug)]
 pub struct ItemAdded {
-    pub character_id: String,
+    pub character_id: CharacterId,
     pub timestamp: String,
-    pub world_id: u8,
-    pub zone_id: u32,
+    pub world_id: WorldId,
+    pub zone_id: ZoneId,
     pub context: String,
     pub item_count: u64,
-    pub item_id: String,
+    pub item_id: ItemId,
 }
 
 impl Event for ItemAdded {
@@ -269,17 +273,17 @@ impl Event for ItemAdded {
             zone_id: parse_zone_id(json)?,
             context: parse_string("context", json)?,
             item_count: try_parse_to("item_count", json)?,
-            item_id: parse_string("item_id", json)?,
+            item_id: try_parse_to("item_id", json)?,
         });
     }
 }
 
 #[derive(Debug)]
 pub struct SkillAdded {
-    pub character_id: String,
+    pub character_id: CharacterId,
     pub timestamp: String,
-    pub world_id: u8,
-    pub zone_id: u32,
+    pub world_id: WorldId,
+    pub zone_id: ZoneId,
     pub skill_id: String,
 }
 
@@ -297,46 +301,46 @@ impl Event for SkillAdded {
 
 #[derive(Debug)]
 pub struct VehicleDestroy {
-    pub attacker_character_id: String,
-    pub attacker_loadout_id: String,
-    pub attacker_vehicle_id: String,
-    pub attacker_weapon_id: String,
-    pub character_id: String,
-    pub facility_id: String,
+    pub attacker_character_id: CharacterId,
+    pub attacker_loadout_id: LoadoutId,
+    pub attacker_vehicle_id: VehicleId,
+    pub attacker_weapon_id: ItemId,
+    pub character_id: CharacterId,
+    pub facility_id: FacilityId,
     pub faction_id: u8,
     pub timestamp: String,
-    pub vehicle_id: String,
-    pub world_id: u8,
-    pub zone_id: u32,
+    pub vehicle_id: VehicleId,
+    pub world_id: WorldId,
+    pub zone_id: ZoneId,
 }
 
 impl Event for VehicleDestroy {
     fn from_json(json: &Value) -> Result<Self, CensusError> {
         return Ok(Self {
-            attacker_character_id: "".to_string(),
-            attacker_loadout_id: "".to_string(),
-            attacker_vehicle_id: "".to_string(),
-            attacker_weapon_id: "".to_string(),
+            attacker_character_id: try_parse_to("attacker_character_id", json)?,
+            attacker_loadout_id: try_parse_to("attacker_loadout_id", json)?,
+            attacker_vehicle_id: try_parse_to("attacker_vehicle_id", json)?,
+            attacker_weapon_id: try_parse_to("attacker_weapon_id", json)?,
             character_id: parse_character_id(json)?,
             timestamp: parse_timestamp(json)?,
             world_id: parse_world_id(json)?,
             zone_id: parse_zone_id(json)?,
-            facility_id: parse_string("facility_id", json)?,
+            facility_id: try_parse_to("facility_id", json)?,
             faction_id: try_parse_to("faction_id", json)?,
-            vehicle_id: parse_string("vehicle_id", json)?,
+            vehicle_id: try_parse_to("vehicle_id", json)?,
         });
     }
 }
 
 #[derive(Debug)]
 pub struct GainExperience {
-    pub character_id: String,
+    pub character_id: CharacterId,
     pub timestamp: String,
-    pub world_id: u8,
-    pub zone_id: u32,
+    pub world_id: WorldId,
+    pub zone_id: ZoneId,
     pub amount: String,
-    pub experience_id: String,
-    pub loudout_id: String,
+    pub experience_id: ExperienceId,
+    pub loadout_id: LoadoutId,
     pub other_id: String,
 }
 
@@ -348,8 +352,8 @@ impl Event for GainExperience {
             world_id: parse_world_id(json)?,
             zone_id: parse_zone_id(json)?,
             amount: parse_string("amount", json)?,
-            experience_id: parse_string("experience_id", json)?,
-            loudout_id: parse_string("loudout_id", json)?,
+            experience_id: try_parse_to("experience_id", json)?,
+            loadout_id: try_parse_to("loadout_id", json)?,
             other_id: parse_string("other_id", json)?,
         });
     }
@@ -357,12 +361,12 @@ impl Event for GainExperience {
 
 #[derive(Debug)]
 pub struct PlayerFacilityCapture {
-    pub character_id: String,
+    pub character_id: CharacterId,
     pub timestamp: String,
-    pub world_id: u8,
-    pub zone_id: u32,
-    pub facility_id: String,
-    pub outfit_id: String,
+    pub world_id: WorldId,
+    pub zone_id: ZoneId,
+    pub facility_id: FacilityId,
+    pub outfit_id: OutfitId,
 }
 
 impl Event for PlayerFacilityCapture {
@@ -372,20 +376,20 @@ impl Event for PlayerFacilityCapture {
             timestamp: parse_timestamp(json)?,
             world_id: parse_world_id(json)?,
             zone_id: parse_zone_id(json)?,
-            facility_id: parse_string("facility_id", json)?,
-            outfit_id: parse_string("outfit_id", json)?,
+            facility_id: try_parse_to("facility_id", json)?,
+            outfit_id: try_parse_to("outfit_id", json)?,
         });
     }
 }
 
 #[derive(Debug)]
 pub struct PlayerFacilityDefend {
-    pub character_id: String,
+    pub character_id: CharacterId,
     pub timestamp: String,
-    pub world_id: u8,
-    pub zone_id: u32,
-    pub facility_id: String,
-    pub outfit_id: String,
+    pub world_id: WorldId,
+    pub zone_id: ZoneId,
+    pub facility_id: FacilityId,
+    pub outfit_id: OutfitId,
 }
 
 impl Event for PlayerFacilityDefend {
@@ -395,8 +399,8 @@ impl Event for PlayerFacilityDefend {
             timestamp: parse_timestamp(json)?,
             world_id: parse_world_id(json)?,
             zone_id: parse_zone_id(json)?,
-            facility_id: parse_string("facility_id", json)?,
-            outfit_id: parse_string("outfit_id", json)?,
+            facility_id: try_parse_to("facility_id", json)?,
+            outfit_id: try_parse_to("outfit_id", json)?,
         });
     }
 }
@@ -404,3 +408,80 @@ impl Event for PlayerFacilityDefend {
 pub trait Event: Sized {
     fn from_json(json: &Value) -> Result<Self, CensusError>;
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::parse_helpers::{parse_bool_from_numstr, parse_zone_id};
+    use super::{Death, Event, GainExperience};
+    use crate::shared::ids::{LoadoutId, WorldId, ZoneId};
+
+    fn death_json(is_headshot: &str) -> serde_json::Value {
+        return json!({
+            "attacker_character_id": "5428010618015189713",
+            "attacker_fire_mode_id": "7388",
+            "attacker_loadout_id": "15",
+            "attacker_vehicle_id": "0",
+            "attacker_weapon_id": "7169",
+            "character_id": "5428124834573913009",
+            "character_loadout_id": "8",
+            "event_name": "Death",
+            "is_headshot": is_headshot,
+            "timestamp": "1700000000",
+            "vehicle_id": "0",
+            "world_id": "17",
+            "zone_id": "2",
+        });
+    }
+
+    #[test]
+    fn bool_from_numstr() {
+        let json = json!({ "yes": "1", "no": "0", "other": "2", "number": 1 });
+
+        assert!(parse_bool_from_numstr("yes", &json).unwrap());
+        assert!(!parse_bool_from_numstr("no", &json).unwrap());
+        assert!(parse_bool_from_numstr("other", &json).is_err());
+        assert!(parse_bool_from_numstr("number", &json).is_err());
+        assert!(parse_bool_from_numstr("missing", &json).is_err());
+    }
+
+    #[test]
+    fn zone_id_is_read_from_zone_id() {
+        let json = json!({ "world_id": "17", "zone_id": "2" });
+
+        assert_eq!(parse_zone_id(&json).unwrap(), ZoneId::new(2));
+    }
+
+    #[test]
+    fn death() {
+        let headshot = Death::from_json(&death_json("1")).unwrap();
+
+        assert!(headshot.is_headshot);
+        assert_eq!(headshot.world_id, WorldId::new(17));
+        assert_eq!(headshot.zone_id, ZoneId::new(2));
+        assert_eq!(headshot.character_loadout_id, LoadoutId::new(8));
+
+        assert!(!Death::from_json(&death_json("0")).unwrap().is_headshot);
+    }
+
+    #[test]
+    fn gain_experience() {
+        let json = json!({
+            "amount": "100",
+            "character_id": "5428124834573913009",
+            "event_name": "GainExperience",
+            "experience_id": "4",
+            "loadout_id": "3",
+            "other_id": "0",
+            "timestamp": "1700000000",
+            "world_id": "17",
+            "zone_id": "2",
+        });
+
+        let event = GainExperience::from_json(&json).unwrap();
+
+        assert_eq!(event.loadout_id, LoadoutId::new(3));
+        assert_eq!(event.zone_id, ZoneId::new(2));
+    }
+}
@@ -2,11 +2,12 @@ use std::str::FromStr;
 
 use serde_json::Value;
 
+use crate::shared::ids::{CharacterId, WorldId, ZoneId};
 use crate::utils::CensusError;
 
 pub fn parse_bool_from_numstr(key: &str, json: &Value) -> Result<bool, CensusError> {
     let v = &json[key];
-    if !v.is_string() {
+    if v.is_string() {
         match v.as_str().unwrap() {
             "0" => {
                 return Ok(false);
@@ -21,7 +22,7 @@ pub fn parse_bool_from_numstr(key: &str, json: &Value) -> Result<bool, CensusErr
     return Err(CensusError {
         err_msg: "Malformed Service Message, could not parse field: '".to_string()
             + key
-            + "' to string.",
+            + "' to bool.",
         parent_err: None,
     });
 }
@@ -65,18 +66,18 @@ pub fn try_parse_to<T: FromStr>(key: &str, json: &Value) -> Result<T, CensusErro
     }
 }
 
-pub fn parse_character_id(json: &Value) -> Result<String, CensusError> {
-    return parse_string("character_id", json);
+pub fn parse_character_id(json: &Value) -> Result<CharacterId, CensusError> {
+    return try_parse_to::<CharacterId>("character_id", json);
 }
 
 pub fn parse_timestamp(json: &Value) -> Result<String, CensusError> {
     return parse_string("timestamp", json);
 }
 
-pub fn parse_world_id(json: &Value) -> Result<u8, CensusError> {
-    return try_parse_to::<u8>("world_id", json);
+pub fn parse_world_id(json: &Value) -> Result<WorldId, CensusError> {
+    return try_parse_to::<WorldId>("world_id", json);
 }
 
-pub fn parse_zone_id(json: &Value) -> Result<u32, CensusError> {
-    return try_parse_to::<u32>("world_id", json);
+pub fn parse_zone_id(json: &Value) -> Result<ZoneId, CensusError> {
+    return try_parse_to::<ZoneId>("zone_id", json);
 }
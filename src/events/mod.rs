@@ -112,7 +112,7 @@ enum RecursionResult {
 }
 
 async fn recursive_next_event_internal(
-    client: &EventClient,
+    _client: &EventClient,
     try_next_ws_msg: Result<Message, CensusError>,
 ) -> RecursionResult {
     match try_next_ws_msg {
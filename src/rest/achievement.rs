@@ -1,16 +1,17 @@
-use std::{sync::Arc, time::SystemTime};
+use std::sync::Arc;
 
-use num_traits::ToPrimitive;
-use serde_json::{json, Value};
+use ps2api_derive::CensusEntity;
 
+use crate::shared::ids::{AchievementId, ItemId};
 use crate::utils::CensusError;
 
-use super::{census_value::CensusValue, item::Item, query_builder::Resolveable, RestClient};
+use super::{census_value::CensusValue, query_builder::Resolveable, LocalisedString, RestClient};
 
+#[derive(Clone, Debug, PartialEq)]
 pub enum AchievementResolves {}
 
 impl Resolveable for AchievementResolves {
-    fn from_resolve_string(resolve: &str) -> Option<Self> {
+    fn from_resolve_string(_resolve: &str) -> Option<Self> {
         return None;
     }
 
@@ -20,76 +21,48 @@ impl Resolveable for AchievementResolves {
 }
 
 ///
-/// Represents a character
+/// Represents an achievement
 ///
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, CensusEntity)]
+#[census(collection = "achievement", resolves = AchievementResolves)]
 pub struct Achievement {
+    #[census(client)]
     pub owning_client: Arc<RestClient>,
-    pub id: u64,
-    pub item_id: CensusValue<String>,
-    pub name: CensusValue<String>,
+    #[census(id = "achievement_id")]
+    pub id: AchievementId,
+    #[census(path = "item_id")]
+    pub item_id: CensusValue<ItemId>,
+    #[census(path = "name")]
+    pub name: CensusValue<LocalisedString>,
+    #[census(path = "reward_id")]
     pub reward_id: CensusValue<String>,
+    #[census(path = "repeatable")]
     pub repeatable: CensusValue<bool>,
-    pub description: CensusValue<String>,
+    #[census(path = "description")]
+    pub description: CensusValue<LocalisedString>,
+    #[census(path = "image_set_id")]
     pub image_set_id: CensusValue<String>,
+    #[census(path = "image_id")]
     pub image_id: CensusValue<String>,
+    #[census(path = "image_path")]
     pub image_path: CensusValue<String>,
 }
 
 impl Achievement {
-    fn update(&mut self, json: &Value) {
-        self.name.update(&json["name"]["en"]);
-        self.item_id.update(&json["item_id"]);
-        self.reward_id.update(&json["reward_id"]);
-        self.repeatable.update(&json["repeatable"]);
-        self.description.update(&json["description"]["en"]);
-        self.image_set_id.update(&json["image_set_id"]);
-        self.image_id.update(&json["image_id"]);
-        self.image_path.update(&json["image_path"]);
-    }
-
-    pub fn new(id: u64, rest_client: Arc<RestClient>) -> Self {
-        Achievement {
-            owning_client: rest_client,
-            id,
-            item_id: CensusValue { last_updated: None, value: None },
-            name: CensusValue { value: None, last_updated: None },
-            reward_id: CensusValue { last_updated: None, value: None },
-            repeatable: CensusValue { last_updated: None, value: None },
-            description: CensusValue { last_updated: None, value: None },
-            image_set_id: CensusValue { last_updated: None, value: None },
-            image_id: CensusValue { last_updated: None, value: None },
-            image_path: CensusValue { last_updated: None, value: None },
-        }
-    }
-
+    /// Fetches the achievement, returning every field that changed
     pub async fn fetch(
         &mut self,
-    ) -> Result<(), CensusError> {
-        let mut query = self.owning_client.get_query_builder("achievement");
-
-        query.limit(1);
+    ) -> Result<Vec<AchievementChange>, CensusError> {
+        let jsonachievement =
+            Achievement::fetch_resolves_json(&self.owning_client, &self.id, None).await?;
 
-        query.search("achievement_id".to_string(), self.id.to_string());
-
-        let char = query.get().await;
-
-        match char {
-            Err(err) => return Err(err),
-            Ok(jsonval) => {
-                let jsonchar = jsonval["achievement_list"][0].clone();
-
-                self.update(&jsonchar);
-
-                return Ok(());
-            }
-        }
+        return Ok(self.update(&jsonachievement));
     }
 
     /// Creates a character and prefetches it
     pub async fn new_prefeched(
         rest_client: Arc<RestClient>,
-        id: u64,
+        id: AchievementId,
     ) -> Result<Self, CensusError> {
         let mut achievement = Achievement::new(id, rest_client);
 
@@ -97,30 +70,4 @@ impl Achievement {
 
         return Ok(achievement);
     }
-
-    //fn from_json_value(json: &Value, rest_client: Arc<RestClient>) -> Result<Self, CensusError> {
-    //    let id_v = &json["character_id"];
-    //
-    //    if !(id_v.is_string() || id_v.is_number()) {
-    //        return Err(CensusError {
-    //            err_msg: "Could not get character id".to_string(),
-    //            parent_err: None,
-    //        });
-    //    }
-    //
-    //
-    //
-    //    if !(id_v.is_string() || id_v.is_number()) {
-    //        return Err(CensusError {
-    //            err_msg: "Could not get character id".to_string(),
-    //            parent_err: None,
-    //        });
-    //    }
-    //
-    //    let mut char = Achievement::new(id_v.to_string(), rest_client);
-    //
-    //    char.update(json);
-    //
-    //    return Ok(char);
-    //}
 }
@@ -1,11 +1,13 @@
 use serde::Deserialize;
 
+#[allow(non_camel_case_types)]
 #[derive(Deserialize)]
 pub enum CharListIndex {
     character(u64),
     return_count(String),
 }
 
+#[allow(non_camel_case_types, clippy::large_enum_variant)]
 #[derive(Deserialize)]
 pub enum CharListVal {
     character(ApiCharacter),
@@ -1,8 +1,15 @@
-use std::{sync::Arc, time::SystemTime};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use num_traits::ToPrimitive;
 use serde_json::Value;
 
+use super::LocalisedString;
+use crate::shared::ids::{
+    AchievementId, CharacterId, ExperienceId, FacilityId, ItemId, LoadoutId, OutfitId, VehicleId,
+    WorldId, ZoneId,
+};
+use crate::shared::worlds::pc;
+
 #[derive(Clone, Debug)]
 pub struct CensusValue<T: Clone> {
     pub last_updated: Option<SystemTime>,
@@ -25,6 +32,92 @@ impl<T: Clone> CensusValue<T> {
     }
 }
 
+impl<T: Clone + PartialEq> CensusValue<T> {
+    /// Stores a new value and reports what it replaced
+    pub fn set(&mut self, value: T) -> CensusUpdate<T> {
+        self.last_updated = Some(SystemTime::now());
+
+        if self.value.as_ref() == Some(&value) {
+            return CensusUpdate::Unchanged;
+        }
+
+        let previous = self.value.replace(value.clone());
+
+        return CensusUpdate::Changed(ValueChange {
+            previous,
+            current: value,
+        });
+    }
+}
+
+///
+/// A value that is parsed from census json as a whole, like the resolved lists of a character
+///
+pub trait FromCensusJson: Sized {
+    /// None if the json does not contain the value, for example when it was not resolved
+    fn from_json(json: &Value) -> Option<Self>;
+
+    /// Parses the json on top of the stored value, values that are built from several resolves
+    /// keep what a partial fetch did not return, everything else is replaced
+    fn merge_json(_current: Option<&Self>, json: &Value) -> Option<Self> {
+        return Self::from_json(json);
+    }
+}
+
+impl<T: FromCensusJson + Clone + PartialEq> CensusValue<T> {
+    pub fn update(&mut self, json: &Value) -> CensusUpdate<T> {
+        match T::merge_json(self.value.as_ref(), json) {
+            Some(v) => {
+                return self.set(v);
+            }
+            None => {
+                return CensusUpdate::Missing;
+            }
+        }
+    }
+}
+
+///
+/// A value that was replaced by an update
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct ValueChange<T> {
+    /// None if the value had never been fetched before
+    pub previous: Option<T>,
+    pub current: T,
+}
+
+///
+/// The outcome of updating a CensusValue from json
+///
+#[derive(Clone, Debug, PartialEq)]
+pub enum CensusUpdate<T> {
+    /// The json did not contain a usable value, nothing was stored
+    Missing,
+    /// The json contained the value that was already stored
+    Unchanged,
+    /// The stored value was replaced
+    Changed(ValueChange<T>),
+}
+
+impl<T> CensusUpdate<T> {
+    /// True if the json contained a value, even if it was the same one
+    pub fn is_updated(&self) -> bool {
+        return !matches!(self, CensusUpdate::Missing);
+    }
+
+    pub fn is_changed(&self) -> bool {
+        return matches!(self, CensusUpdate::Changed(_));
+    }
+
+    pub fn change(self) -> Option<ValueChange<T>> {
+        match self {
+            CensusUpdate::Changed(change) => Some(change),
+            _ => None,
+        }
+    }
+}
+
 impl CensusValue<String> {
     pub fn from_json(json: &Value) -> CensusValue<String> {
         let mut val: CensusValue<String> = CensusValue {
@@ -37,15 +130,12 @@ impl CensusValue<String> {
         return val;
     }
 
-    pub fn update(&mut self, json: &Value) -> bool {
+    pub fn update(&mut self, json: &Value) -> CensusUpdate<String> {
         if json.is_string() {
-            self.value = Some(json.as_str().unwrap().to_string());
-            self.last_updated = Some(SystemTime::now());
-
-            return true;
+            return self.set(json.as_str().unwrap().to_string());
         }
 
-        return false;
+        return CensusUpdate::Missing;
     }
 }
 
@@ -61,7 +151,7 @@ impl CensusValue<u8> {
         return val;
     }
 
-    pub fn update(&mut self, json: &Value) -> bool {
+    pub fn update(&mut self, json: &Value) -> CensusUpdate<u8> {
         if json.is_number() {
             let try_val = json.as_u64();
 
@@ -70,9 +160,7 @@ impl CensusValue<u8> {
                 Some(v_64) => match v_64.to_u8() {
                     None => {}
                     Some(v) => {
-                        self.value = Some(v);
-                        self.last_updated = Some(SystemTime::now());
-                        return true;
+                        return self.set(v);
                     }
                 },
             }
@@ -85,14 +173,12 @@ impl CensusValue<u8> {
             match try_val {
                 Err(_) => {}
                 Ok(v) => {
-                    self.value = Some(v);
-                    self.last_updated = Some(SystemTime::now());
-                    return true;
+                    return self.set(v);
                 }
             }
         }
 
-        return false;
+        return CensusUpdate::Missing;
     }
 
     pub fn percent_from_float_json(json: &Value) -> CensusValue<u8> {
@@ -104,7 +190,7 @@ impl CensusValue<u8> {
         return val;
     }
 
-    pub fn percent_update_float_json(&mut self, json: &Value) -> bool {
+    pub fn percent_update_float_json(&mut self, json: &Value) -> CensusUpdate<u8> {
         if json.is_number() {
             let try_val = json.as_f64();
 
@@ -114,17 +200,15 @@ impl CensusValue<u8> {
 
                     match v_f64.to_u8() {
                         Some(v) => {
-                            self.value = Some(v);
-                            self.last_updated = Some(SystemTime::now());
-                            return true;
+                            return self.set(v);
                         }
                         None => {
-                            return false;
+                            return CensusUpdate::Missing;
                         }
                     };
                 }
                 None => {
-                    return false;
+                    return CensusUpdate::Missing;
                 }
             };
         };
@@ -140,19 +224,17 @@ impl CensusValue<u8> {
 
                     match v_f64.to_u8() {
                         Some(v) => {
-                            self.value = Some(v);
-                            self.last_updated = Some(SystemTime::now());
-                            return true;
+                            return self.set(v);
                         }
                         None => {
-                            return false;
+                            return CensusUpdate::Missing;
                         }
                     };
                 }
             };
         };
 
-        return false;
+        return CensusUpdate::Missing;
     }
 }
 
@@ -168,17 +250,12 @@ impl CensusValue<u64> {
         return val;
     }
 
-    pub fn update(&mut self, json: &Value) -> bool {
+    pub fn update(&mut self, json: &Value) -> CensusUpdate<u64> {
         if json.is_number() {
             let try_val = json.as_u64();
 
-            match try_val {
-                Some(v) => {
-                    self.value = Some(v);
-                    self.last_updated = Some(SystemTime::now());
-                    return true;
-                }
-                None => {}
+            if let Some(v) = try_val {
+                return self.set(v);
             }
         }
 
@@ -189,14 +266,12 @@ impl CensusValue<u64> {
             match try_val {
                 Err(_) => {}
                 Ok(v) => {
-                    self.value = Some(v);
-                    self.last_updated = Some(SystemTime::now());
-                    return true;
+                    return self.set(v);
                 }
             }
         }
 
-        return false;
+        return CensusUpdate::Missing;
     }
 }
 
@@ -211,33 +286,25 @@ impl CensusValue<bool> {
 
         return val;
     }
-    pub fn update(&mut self, json: &Value) -> bool {
+    pub fn update(&mut self, json: &Value) -> CensusUpdate<bool> {
         if json.is_string() {
             let str = json.as_str().unwrap();
 
             match str.to_lowercase().as_str() {
                 "true" => {
-                    self.value = Some(true);
-                    self.last_updated = Some(SystemTime::now());
-                    return true;
+                    return self.set(true);
                 }
                 "false" => {
-                    self.value = Some(false);
-                    self.last_updated = Some(SystemTime::now());
-                    return true;
+                    return self.set(false);
                 }
                 "1" => {
-                    self.value = Some(true);
-                    self.last_updated = Some(SystemTime::now());
-                    return true;
+                    return self.set(true);
                 }
                 "0" => {
-                    self.value = Some(false);
-                    self.last_updated = Some(SystemTime::now());
-                    return true;
+                    return self.set(false);
                 }
                 _ => {
-                    return false;
+                    return CensusUpdate::Missing;
                 }
             }
         }
@@ -247,12 +314,10 @@ impl CensusValue<bool> {
 
             match try_bool {
                 None => {
-                    return false;
+                    return CensusUpdate::Missing;
                 }
                 Some(v) => {
-                    self.value = Some(v);
-                    self.last_updated = Some(SystemTime::now());
-                    return true;
+                    return self.set(v);
                 }
             }
         }
@@ -264,22 +329,296 @@ impl CensusValue<bool> {
                 None => {}
                 Some(num) => match num {
                     0 => {
-                        self.value = Some(false);
-                        self.last_updated = Some(SystemTime::now());
-                        return true;
+                        return self.set(false);
                     }
                     1 => {
-                        self.value = Some(true);
-                        self.last_updated = Some(SystemTime::now());
-                        return true;
+                        return self.set(true);
                     }
                     _ => {
-                        return false;
+                        return CensusUpdate::Missing;
                     }
                 },
             }
         }
 
-        return false;
+        return CensusUpdate::Missing;
+    }
+}
+
+impl CensusValue<LocalisedString> {
+    pub fn from_json(json: &Value) -> Self {
+        let mut val: CensusValue<LocalisedString> = CensusValue {
+            value: None,
+            last_updated: None,
+        };
+
+        val.update(json);
+
+        return val;
+    }
+
+    pub fn update(&mut self, json: &Value) -> CensusUpdate<LocalisedString> {
+        match LocalisedString::from_json(json) {
+            Some(v) => {
+                return self.set(v);
+            }
+            None => {
+                return CensusUpdate::Missing;
+            }
+        }
+    }
+}
+
+impl CensusValue<SystemTime> {
+    /// Updates from a unix timestamp in seconds, like the time_created of an outfit
+    pub fn update(&mut self, json: &Value) -> CensusUpdate<SystemTime> {
+        let seconds = match json {
+            Value::Number(n) => n.as_u64(),
+            Value::String(s) => s.parse::<u64>().ok(),
+            _ => None,
+        };
+
+        match seconds {
+            Some(v) => {
+                return self.set(UNIX_EPOCH + Duration::from_secs(v));
+            }
+            None => {
+                return CensusUpdate::Missing;
+            }
+        }
+    }
+}
+
+impl CensusValue<pc::Worlds> {
+    /// Updates from a world id, only pc worlds are known
+    pub fn update(&mut self, json: &Value) -> CensusUpdate<pc::Worlds> {
+        match json
+            .as_str()
+            .and_then(|s| s.parse::<u16>().ok())
+            .and_then(pc::Worlds::from_id)
+        {
+            Some(v) => {
+                return self.set(v);
+            }
+            None => {
+                return CensusUpdate::Missing;
+            }
+        }
+    }
+}
+
+macro_rules! census_id_value {
+    ($($id:ident),*) => {
+        $(
+            impl CensusValue<$id> {
+                pub fn from_json(json: &Value) -> Self {
+                    let mut val: CensusValue<$id> = CensusValue {
+                        value: None,
+                        last_updated: None,
+                    };
+
+                    val.update(json);
+
+                    return val;
+                }
+
+                pub fn update(&mut self, json: &Value) -> CensusUpdate<$id> {
+                    if let Some(id) = json.as_u64().and_then(num_traits::cast) {
+                        return self.set($id::new(id));
+                    }
+
+                    if let Some(Ok(id)) = json.as_str().map(|s| s.parse::<$id>()) {
+                        return self.set(id);
+                    }
+
+                    return CensusUpdate::Missing;
+                }
+            }
+        )*
+    };
+}
+
+census_id_value!(
+    CharacterId,
+    OutfitId,
+    ItemId,
+    AchievementId,
+    WorldId,
+    ZoneId,
+    FacilityId,
+    ExperienceId,
+    VehicleId,
+    LoadoutId
+);
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use serde_json::json;
+
+    use super::{CensusUpdate, CensusValue, ValueChange};
+    use crate::rest::character::stats::{CharacterStats, StatPeriod};
+    use crate::rest::character::weapon_stats::CharacterWeaponStats;
+    use crate::rest::character::{Character, CharacterChange, CharacterField, CharacterResolves};
+    use crate::rest::registry::RegistryEntity;
+    use crate::rest::LocalisedString;
+    use crate::rest::RestClient;
+    use crate::shared::ids::{CharacterId, ItemId};
+
+    #[test]
+    fn set_reports_changes() {
+        let mut value: CensusValue<u8> = CensusValue::<u8>::new_empty();
+
+        assert_eq!(
+            value.set(20),
+            CensusUpdate::Changed(ValueChange {
+                previous: None,
+                current: 20,
+            })
+        );
+        assert_eq!(value.set(20), CensusUpdate::Unchanged);
+        assert_eq!(
+            value.set(21),
+            CensusUpdate::Changed(ValueChange {
+                previous: Some(20),
+                current: 21,
+            })
+        );
+        assert_eq!(value.value, Some(21));
+        assert!(value.last_updated.is_some());
+    }
+
+    #[test]
+    fn missing_values_are_kept() {
+        let mut value = CensusValue::<u8>::from_json(&json!("20"));
+
+        assert_eq!(value.update(&json!(null)), CensusUpdate::Missing);
+        assert_eq!(value.update(&json!("not a number")), CensusUpdate::Missing);
+        assert_eq!(value.value, Some(20));
+
+        assert!(!CensusUpdate::<u8>::Missing.is_updated());
+        assert!(CensusUpdate::<u8>::Unchanged.is_updated());
+        assert!(!CensusUpdate::<u8>::Unchanged.is_changed());
+    }
+
+    #[test]
+    fn character_battle_rank_change() {
+        let rest_client = Arc::new(RestClient::new("example".to_string()));
+        let mut character = Character::new(CharacterId::new(5428010618015189713), rest_client);
+
+        let first = character.update_entity(&json!({
+            "character_id": "5428010618015189713",
+            "name": { "first": "Example" },
+            "battle_rank": { "value": "20" },
+        }));
+
+        assert!(first.contains(&CharacterChange::BattleRank(ValueChange {
+            previous: None,
+            current: 20,
+        })));
+
+        let second = character.update_entity(&json!({
+            "character_id": "5428010618015189713",
+            "name": { "first": "Example" },
+            "battle_rank": { "value": "21" },
+        }));
+
+        assert_eq!(
+            second,
+            vec![CharacterChange::BattleRank(ValueChange {
+                previous: Some(20),
+                current: 21,
+            })]
+        );
+        assert_eq!(character.battle_rank.value, Some(21));
+        assert_eq!(character.name.value.as_deref(), Some("Example"));
+    }
+
+    #[test]
+    fn weapon_stats_merge_partial_resolves() {
+        let mut value: CensusValue<CharacterWeaponStats> = CensusValue::<u8>::new_empty();
+
+        value.update(&json!({
+            "weapon_stat": [
+                { "stat_name": "weapon_deaths", "item_id": "7214", "vehicle_id": "0", "value": "12" },
+            ],
+        }));
+
+        let name = LocalisedString {
+            en: Some("Gauss Rifle".to_string()),
+            ..Default::default()
+        };
+        let mut named = value.value.clone().unwrap();
+        named
+            .weapons
+            .values_mut()
+            .for_each(|w| w.name = Some(name.clone()));
+        value.set(named);
+
+        value.update(&json!({
+            "weapon_stat_by_faction": [
+                {
+                    "stat_name": "weapon_kills",
+                    "item_id": "7214",
+                    "vehicle_id": "0",
+                    "value_vs": "1",
+                    "value_nc": "2",
+                    "value_tr": "3",
+                },
+            ],
+        }));
+
+        let weapon = value
+            .value
+            .as_ref()
+            .unwrap()
+            .get(ItemId::new(7214))
+            .unwrap();
+        assert_eq!(weapon.deaths, 12);
+        assert_eq!(weapon.kills.total(), 6);
+        assert_eq!(weapon.name, Some(name));
+    }
+
+    #[test]
+    fn stats_merge_partial_resolves() {
+        let mut value: CensusValue<CharacterStats> = CensusValue::<u8>::new_empty();
+
+        value.update(&json!({
+            "stat_by_faction": [
+                {
+                    "stat_name": "weapon_kills",
+                    "profile_id": "0",
+                    "value_forever_vs": "10",
+                    "value_forever_nc": "20",
+                    "value_forever_tr": "30",
+                },
+            ],
+        }));
+
+        value.update(&json!({
+            "stat": [
+                { "stat_name": "weapon_deaths", "profile_id": "0", "value_forever": "40" },
+            ],
+        }));
+
+        let stats = value.value.as_ref().unwrap();
+        assert_eq!(stats.total().kills(StatPeriod::Forever), 60);
+        assert_eq!(stats.total().deaths(StatPeriod::Forever), 40);
+    }
+
+    #[test]
+    fn stats_fields_request_every_resolve() {
+        assert_eq!(
+            Character::resolves_for(&[CharacterField::Stats]),
+            vec![CharacterResolves::Stat, CharacterResolves::StatByFaction]
+        );
+        assert_eq!(
+            Character::resolves_for(&[CharacterField::WeaponStats]),
+            vec![
+                CharacterResolves::WeaponStat,
+                CharacterResolves::WeaponStatByFaction
+            ]
+        );
     }
 }
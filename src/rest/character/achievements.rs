@@ -0,0 +1,222 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use futures_util::future::try_join;
+use serde_json::Value;
+
+use crate::rest::achievement::Achievement;
+use crate::rest::census_value::FromCensusJson;
+use crate::rest::{query_builder::Join, RestClient};
+use crate::shared::ids::{CharacterId, ItemId};
+use crate::utils::{parse_nonzero_u64, parse_u64, CensusError};
+
+use super::weapon_stats::CharacterWeaponStats;
+use super::{Character, CharacterResolves};
+
+/// The rows fetched from characters_achievement per request, census returns at most 5000
+const ACHIEVEMENT_PAGE_SIZE: u64 = 5000;
+
+///
+/// The progress of a character on a single achievement, from characters_achievement
+///
+#[derive(Clone, Debug)]
+pub struct CharacterAchievement {
+    pub achievement: Achievement,
+    /// How often the achievement was completed, only above 1 for repeatable achievements
+    pub earned_count: u64,
+    /// Unix timestamp in seconds
+    pub start: Option<u64>,
+    /// Unix timestamp in seconds, None while the achievement is in progress
+    pub finish: Option<u64>,
+    /// Unix timestamp in seconds
+    pub last_save: Option<u64>,
+    /// The count the objective of the achievement requires, like the kills of a weapon medal
+    pub goal: Option<u64>,
+    /// The progress towards the goal, census only tracks this for achievements tied to a weapon,
+    /// where the progress are the kills made with it
+    pub progress: Option<u64>,
+}
+
+impl CharacterAchievement {
+    fn from_json(
+        json: &Value,
+        weapon_kills: &HashMap<ItemId, u64>,
+        rest_client: Arc<RestClient>,
+    ) -> Option<CharacterAchievement> {
+        let achievement = match json["achievement"].is_object() {
+            true => Achievement::from_json_value(&json["achievement"], rest_client).ok()?,
+            false => Achievement::new(json["achievement_id"].as_str()?.parse().ok()?, rest_client),
+        };
+
+        let progress = achievement
+            .item_id
+            .value
+            .map(|item_id| weapon_kills.get(&item_id).copied().unwrap_or(0));
+
+        return Some(CharacterAchievement {
+            achievement,
+            earned_count: parse_u64(&json["earned_count"]),
+            start: parse_nonzero_u64(&json["start"]),
+            finish: parse_nonzero_u64(&json["finish"]),
+            last_save: parse_nonzero_u64(&json["last_save"]),
+            goal: parse_nonzero_u64(&json["achievement"]["objective"]["param1"]),
+            progress,
+        });
+    }
+
+    pub fn is_completed(&self) -> bool {
+        return self.finish.is_some();
+    }
+
+    /// The progress towards the goal between 0 and 1, None if census does not track the progress
+    pub fn completion(&self) -> Option<f64> {
+        if self.is_completed() {
+            return Some(1_f64);
+        }
+
+        let goal = self.goal?;
+        let progress = self.progress?;
+
+        return Some((progress as f64 / goal as f64).min(1_f64));
+    }
+}
+
+///
+/// Every achievement a character started or completed
+///
+/// The progress of weapon achievements is taken from the weapon stats fetched with them.
+///
+#[derive(Clone, Debug, Default)]
+pub struct CharacterAchievements {
+    pub achievements: Vec<CharacterAchievement>,
+    pub weapon_stats: CharacterWeaponStats,
+}
+
+impl CharacterAchievements {
+    /// Fetches characters_achievement joined with the achievement definitions and their objective,
+    /// page by page, along with the weapon stats of the character
+    pub async fn fetch(
+        rest_client: Arc<RestClient>,
+        character_id: CharacterId,
+    ) -> Result<CharacterAchievements, CensusError> {
+        let (rows, jsonchar) = try_join(
+            CharacterAchievements::fetch_rows(&rest_client, character_id),
+            Character::fetch_resolves_json(
+                &rest_client,
+                &character_id,
+                Some(vec![
+                    CharacterResolves::WeaponStat,
+                    CharacterResolves::WeaponStatByFaction,
+                ]),
+            ),
+        )
+        .await?;
+
+        let weapon_stats = CharacterWeaponStats::from_json(&jsonchar["stats"]).unwrap_or_default();
+
+        // a weapon mounted on several vehicles has stats for each of them
+        let mut weapon_kills: HashMap<ItemId, u64> = HashMap::new();
+
+        for weapon in weapon_stats.iter() {
+            *weapon_kills.entry(weapon.item_id).or_insert(0) += weapon.kills.total();
+        }
+
+        let achievements = rows
+            .iter()
+            .filter_map(|json| {
+                CharacterAchievement::from_json(json, &weapon_kills, rest_client.clone())
+            })
+            .collect();
+
+        return Ok(CharacterAchievements {
+            achievements,
+            weapon_stats,
+        });
+    }
+
+    async fn fetch_rows(
+        rest_client: &RestClient,
+        character_id: CharacterId,
+    ) -> Result<Vec<Value>, CensusError> {
+        let mut rows = Vec::new();
+        let mut start = 0;
+
+        loop {
+            let mut query = rest_client.get_query_builder("characters_achievement");
+
+            query.search("character_id".to_string(), character_id.to_string());
+
+            let mut objective = Join::new("objective");
+            objective.on("objective_group_id");
+            objective.inject_at("objective");
+            objective.show(vec!["param1"]);
+
+            let mut achievement = Join::new(Achievement::COLLECTION);
+            achievement.on(Achievement::ID_FIELD);
+            achievement.inject_at("achievement");
+            achievement.join(objective);
+
+            query.join(achievement);
+
+            query.start(start);
+            query.limit(ACHIEVEMENT_PAGE_SIZE);
+
+            let mut jsonval = query.get().await?;
+
+            let page = match jsonval["characters_achievement_list"].take() {
+                Value::Array(page) => page,
+                _ => Vec::new(),
+            };
+
+            let is_last = (page.len() as u64) < ACHIEVEMENT_PAGE_SIZE;
+
+            rows.extend(page);
+
+            if is_last {
+                return Ok(rows);
+            }
+
+            start += ACHIEVEMENT_PAGE_SIZE;
+        }
+    }
+
+    pub fn completed(&self) -> impl Iterator<Item = &CharacterAchievement> {
+        return self.achievements.iter().filter(|a| a.is_completed());
+    }
+
+    pub fn in_progress(&self) -> impl Iterator<Item = &CharacterAchievement> {
+        return self.achievements.iter().filter(|a| !a.is_completed());
+    }
+
+    /// The unfinished achievements with known progress, the closest to completion first
+    ///
+    /// Achievements census does not track the progress of are left out, see without_progress.
+    pub fn closest_to_completion(&self, limit: usize) -> Vec<(&CharacterAchievement, f64)> {
+        let mut closest: Vec<(&CharacterAchievement, f64)> = self
+            .in_progress()
+            .filter_map(|a| a.completion().map(|c| (a, c)))
+            .collect();
+
+        closest.sort_by(|a, b| b.1.total_cmp(&a.1));
+        closest.truncate(limit);
+
+        return closest;
+    }
+
+    /// The unfinished achievements whose progress is unknown, like every achievement not tied to a weapon
+    pub fn without_progress(&self) -> impl Iterator<Item = &CharacterAchievement> {
+        return self.in_progress().filter(|a| a.completion().is_none());
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, CharacterAchievement> {
+        return self.achievements.iter();
+    }
+
+    pub fn len(&self) -> usize {
+        return self.achievements.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.achievements.is_empty();
+    }
+}
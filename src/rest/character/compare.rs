@@ -0,0 +1,304 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use serde::Serialize;
+
+use crate::rest::{item::Item, RestClient};
+use crate::shared::ids::{CharacterId, ItemId, OutfitId};
+use crate::utils::{per_minute, ratio, to_json_pretty, CensusError};
+
+use super::stat_history::{CharacterStatHistory, HistoryStat};
+use super::stats::{CharacterStats, StatPeriod};
+use super::weapon_stats::CharacterWeaponStats;
+use super::{Character, CharacterResolves};
+
+/// The amount of days of stat history counted as recent
+const RECENT_DAYS: usize = 30;
+/// The amount of weapons listed per character
+const TOP_WEAPONS: usize = 5;
+
+///
+/// Kills, deaths, score and play time over a period with the ratios derived from them
+///
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct StatSummary {
+    pub kills: u64,
+    pub deaths: u64,
+    pub score: u64,
+    /// In seconds
+    pub play_time: u64,
+    pub kdr: f64,
+    pub kpm: f64,
+    pub spm: f64,
+}
+
+impl StatSummary {
+    fn new(kills: u64, deaths: u64, score: u64, play_time: u64) -> StatSummary {
+        return StatSummary {
+            kills,
+            deaths,
+            score,
+            play_time,
+            kdr: ratio(kills, deaths),
+            kpm: per_minute(kills, play_time),
+            spm: per_minute(score, play_time),
+        };
+    }
+
+    fn lifetime(stats: &CharacterStats) -> StatSummary {
+        let total = stats.total();
+        let period = StatPeriod::Forever;
+
+        return StatSummary::new(
+            total.kills(period),
+            total.deaths(period),
+            total.score(period),
+            total.play_time(period),
+        );
+    }
+
+    fn recent(history: &CharacterStatHistory) -> StatSummary {
+        let sum = |stat: HistoryStat| -> u64 {
+            return history
+                .get(stat)
+                .map(|h| h.last_days(RECENT_DAYS))
+                .unwrap_or(0);
+        };
+
+        return StatSummary::new(
+            sum(HistoryStat::Kills),
+            sum(HistoryStat::Deaths),
+            sum(HistoryStat::Score),
+            sum(HistoryStat::Time),
+        );
+    }
+}
+
+///
+/// A weapon of a character with its most compared stats
+///
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct WeaponSummary {
+    pub item_id: ItemId,
+    /// The english name, only set if the weapon names were fetched
+    pub name: Option<String>,
+    pub kills: u64,
+    pub kdr: f64,
+    pub kpm: f64,
+    pub accuracy: f64,
+    pub headshot_rate: f64,
+}
+
+impl WeaponSummary {
+    /// The weapons with the most kills, vehicle stats without a weapon are left out
+    fn top(weapon_stats: &CharacterWeaponStats, count: usize) -> Vec<WeaponSummary> {
+        let mut weapons: Vec<WeaponSummary> = weapon_stats
+            .iter()
+            .filter(|w| w.item_id.value() != 0)
+            .map(|w| WeaponSummary {
+                item_id: w.item_id,
+                name: w
+                    .name
+                    .as_ref()
+                    .and_then(|n| n.english())
+                    .map(|n| n.to_string()),
+                kills: w.kills.total(),
+                kdr: w.kdr(),
+                kpm: w.kpm(),
+                accuracy: w.accuracy(),
+                headshot_rate: w.headshot_rate(),
+            })
+            .collect();
+
+        weapons.sort_by_key(|w| (std::cmp::Reverse(w.kills), w.item_id));
+        weapons.truncate(count);
+
+        return weapons;
+    }
+}
+
+///
+/// One character of a comparison, fields are None if the character was fetched without them
+///
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ComparedCharacter {
+    pub id: CharacterId,
+    pub name: Option<String>,
+    pub faction_id: Option<u8>,
+    pub battle_rank: Option<u8>,
+    pub minutes_played: Option<u64>,
+    /// From the stat and stat_by_faction resolves
+    pub lifetime: Option<StatSummary>,
+    /// The last 30 days, from the stat_history resolve
+    pub recent: Option<StatSummary>,
+    /// From the weapon_stat and weapon_stat_by_faction resolves
+    pub top_weapons: Vec<WeaponSummary>,
+    pub outfit_id: Option<OutfitId>,
+    pub outfit_name: Option<String>,
+}
+
+impl ComparedCharacter {
+    fn from_character(character: &Character) -> ComparedCharacter {
+        return ComparedCharacter {
+            id: character.id,
+            name: character.name.value.clone(),
+            faction_id: character.faction_id.value,
+            battle_rank: character.battle_rank.value,
+            minutes_played: character.minutes_played.value,
+            lifetime: character.stats.value.as_ref().map(StatSummary::lifetime),
+            recent: character
+                .stat_history
+                .value
+                .as_ref()
+                .map(StatSummary::recent),
+            top_weapons: character
+                .weapon_stats
+                .value
+                .as_ref()
+                .map(|w| WeaponSummary::top(w, TOP_WEAPONS))
+                .unwrap_or_default(),
+            // census uses 0 for characters without an outfit
+            outfit_id: character.outfit_id.value.filter(|id| id.value() != 0),
+            outfit_name: character.outfit_name.value.clone(),
+        };
+    }
+}
+
+///
+/// An outfit several of the compared characters are currently in
+///
+/// Census only exposes the current outfit of a character, earlier memberships are not known,
+/// so outfits the characters shared in the past can not be reported.
+///
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct SharedOutfit {
+    pub outfit_id: OutfitId,
+    pub name: Option<String>,
+    pub character_ids: Vec<CharacterId>,
+}
+
+///
+/// A side by side report of two or more characters
+///
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct CharacterComparison {
+    pub characters: Vec<ComparedCharacter>,
+    /// Only the current outfits, see SharedOutfit
+    pub shared_outfits: Vec<SharedOutfit>,
+}
+
+impl CharacterComparison {
+    /// The resolves every section of the report is built from
+    pub fn resolves() -> Vec<CharacterResolves> {
+        return vec![
+            CharacterResolves::Stat,
+            CharacterResolves::StatByFaction,
+            CharacterResolves::WeaponStat,
+            CharacterResolves::WeaponStatByFaction,
+            CharacterResolves::StatHistory,
+            CharacterResolves::Outfit,
+        ];
+    }
+
+    /// Builds the report from already fetched characters, see resolves for what they should be fetched with
+    pub fn new(characters: &[&Character]) -> CharacterComparison {
+        let compared: Vec<ComparedCharacter> = characters
+            .iter()
+            .map(|c| ComparedCharacter::from_character(c))
+            .collect();
+
+        let mut shared_outfits: Vec<SharedOutfit> = Vec::new();
+
+        for character in &compared {
+            let outfit_id = match character.outfit_id {
+                Some(outfit_id) => outfit_id,
+                None => continue,
+            };
+
+            match shared_outfits.iter_mut().find(|o| o.outfit_id == outfit_id) {
+                Some(outfit) => {
+                    outfit.character_ids.push(character.id);
+                }
+                None => {
+                    shared_outfits.push(SharedOutfit {
+                        outfit_id,
+                        name: character.outfit_name.clone(),
+                        character_ids: vec![character.id],
+                    });
+                }
+            }
+        }
+
+        shared_outfits.retain(|o| o.character_ids.len() > 1);
+
+        return CharacterComparison {
+            characters: compared,
+            shared_outfits,
+        };
+    }
+
+    /// Fetches the characters with every resolve the report needs and the names of their top weapons
+    pub async fn fetch(
+        rest_client: Arc<RestClient>,
+        ids: &[CharacterId],
+    ) -> Result<CharacterComparison, CensusError> {
+        let mut characters = Character::fetch_many(
+            rest_client.clone(),
+            ids,
+            Some(CharacterComparison::resolves()),
+        )
+        .await?;
+
+        // keep the order the ids were given in
+        characters.sort_by_key(|c| ids.iter().position(|id| *id == c.id));
+
+        let refs: Vec<&Character> = characters.iter().collect();
+
+        let mut comparison = CharacterComparison::new(&refs);
+
+        comparison.fetch_weapon_names(&rest_client).await?;
+
+        return Ok(comparison);
+    }
+
+    /// Fetches the names of the top weapons of every character at once
+    pub async fn fetch_weapon_names(
+        &mut self,
+        rest_client: &RestClient,
+    ) -> Result<(), CensusError> {
+        let mut ids: Vec<ItemId> = self
+            .characters
+            .iter()
+            .flat_map(|c| c.top_weapons.iter().map(|w| w.item_id))
+            .collect();
+
+        ids.sort();
+        ids.dedup();
+
+        let items: HashMap<ItemId, Item> = Item::fetch_many(rest_client, &ids)
+            .await?
+            .into_iter()
+            .map(|item| (item.id, item))
+            .collect();
+
+        for weapon in self
+            .characters
+            .iter_mut()
+            .flat_map(|c| c.top_weapons.iter_mut())
+        {
+            if let Some(name) = items
+                .get(&weapon.item_id)
+                .and_then(|item| item.name.as_ref())
+                .and_then(|name| name.english())
+            {
+                weapon.name = Some(name.to_string());
+            }
+        }
+
+        return Ok(());
+    }
+
+    pub fn to_json(&self) -> Result<String, CensusError> {
+        return to_json_pretty(self, "character comparison");
+    }
+}
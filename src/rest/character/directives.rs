@@ -0,0 +1,244 @@
+use futures_util::future::try_join4;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::rest::{query_builder::Join, LocalisedString, RestClient};
+use crate::shared::ids::CharacterId;
+use crate::utils::{parse_nonzero_u64, parse_u64, CensusError};
+
+use super::fetch_character_rows;
+
+/// The most rows fetched from a single characters_directive_* collection
+const DIRECTIVE_LIMIT: u64 = 5000;
+
+///
+/// The progress of a character in a directive tree, from characters_directive_tree
+///
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct DirectiveTreeProgress {
+    pub tree_id: u64,
+    pub current_tier_id: u64,
+    pub current_level: u64,
+    /// Unix timestamp in seconds, None while the tree is in progress
+    pub completion_time: Option<u64>,
+    /// Only set when the definitions were joined
+    pub name: Option<LocalisedString>,
+}
+
+impl DirectiveTreeProgress {
+    fn from_json(json: &Value) -> Option<DirectiveTreeProgress> {
+        return Some(DirectiveTreeProgress {
+            tree_id: json["directive_tree_id"].as_str()?.parse().ok()?,
+            current_tier_id: parse_u64(&json["current_directive_tier_id"]),
+            current_level: parse_u64(&json["current_level"]),
+            completion_time: parse_nonzero_u64(&json["completion_time"]),
+            name: LocalisedString::from_json(&json["directive_tree"]["name"]),
+        });
+    }
+
+    pub fn is_completed(&self) -> bool {
+        return self.completion_time.is_some();
+    }
+}
+
+///
+/// A completed tier of a directive tree, from characters_directive_tier
+///
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct DirectiveTierProgress {
+    pub tree_id: u64,
+    /// Tier ids are only unique within their tree
+    pub tier_id: u64,
+    /// Unix timestamp in seconds
+    pub completion_time: Option<u64>,
+    /// Only set when the definitions were joined
+    pub name: Option<LocalisedString>,
+}
+
+impl DirectiveTierProgress {
+    fn from_json(json: &Value) -> Option<DirectiveTierProgress> {
+        let tier_id: u64 = json["directive_tier_id"].as_str()?.parse().ok()?;
+
+        // the join can only match on the tree, so the tier is picked from every tier of the tree
+        let name = json["directive_tiers"].as_array().and_then(|tiers| {
+            return tiers
+                .iter()
+                .find(|t| parse_u64(&t["directive_tier_id"]) == tier_id)
+                .and_then(|t| LocalisedString::from_json(&t["name"]));
+        });
+
+        return Some(DirectiveTierProgress {
+            tree_id: json["directive_tree_id"].as_str()?.parse().ok()?,
+            tier_id,
+            completion_time: parse_nonzero_u64(&json["completion_time"]),
+            name,
+        });
+    }
+}
+
+///
+/// A completed directive, from characters_directive
+///
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct DirectiveProgress {
+    pub directive_id: u64,
+    pub tree_id: u64,
+    /// Unix timestamp in seconds
+    pub completion_time: Option<u64>,
+    /// Only set when the definitions were joined
+    pub name: Option<LocalisedString>,
+}
+
+impl DirectiveProgress {
+    fn from_json(json: &Value) -> Option<DirectiveProgress> {
+        return Some(DirectiveProgress {
+            directive_id: json["directive_id"].as_str()?.parse().ok()?,
+            tree_id: parse_u64(&json["directive_tree_id"]),
+            completion_time: parse_nonzero_u64(&json["completion_time"]),
+            name: LocalisedString::from_json(&json["directive"]["name"]),
+        });
+    }
+}
+
+///
+/// The counter of a directive objective, from characters_directive_objective
+///
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct DirectiveObjectiveProgress {
+    pub directive_id: u64,
+    pub objective_id: u64,
+    pub objective_group_id: u64,
+    pub status: u64,
+    /// The counter of the objective, like the kills made towards it
+    pub state_data: u64,
+}
+
+impl DirectiveObjectiveProgress {
+    fn from_json(json: &Value) -> Option<DirectiveObjectiveProgress> {
+        return Some(DirectiveObjectiveProgress {
+            directive_id: json["directive_id"].as_str()?.parse().ok()?,
+            objective_id: json["objective_id"].as_str()?.parse().ok()?,
+            objective_group_id: parse_u64(&json["objective_group_id"]),
+            status: parse_u64(&json["status"]),
+            state_data: parse_u64(&json["state_data"]),
+        });
+    }
+}
+
+///
+/// The directive progress of a character
+///
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct CharacterDirectives {
+    pub trees: Vec<DirectiveTreeProgress>,
+    pub tiers: Vec<DirectiveTierProgress>,
+    pub directives: Vec<DirectiveProgress>,
+    pub objectives: Vec<DirectiveObjectiveProgress>,
+}
+
+impl CharacterDirectives {
+    /// Fetches every directive collection of a character, optionally joining the names of the static definitions
+    pub async fn fetch(
+        rest_client: &RestClient,
+        character_id: CharacterId,
+        join_definitions: bool,
+    ) -> Result<CharacterDirectives, CensusError> {
+        let (trees, tiers, directives, objectives) = try_join4(
+            fetch_character_rows(
+                rest_client,
+                character_id,
+                "characters_directive_tree",
+                join_definitions.then(|| definition_join("directive_tree", "directive_tree_id")),
+                DIRECTIVE_LIMIT,
+            ),
+            fetch_character_rows(
+                rest_client,
+                character_id,
+                "characters_directive_tier",
+                join_definitions.then(|| {
+                    let mut join = Join::new("directive_tier");
+                    join.on("directive_tree_id");
+                    join.list(true);
+                    join.inject_at("directive_tiers");
+                    join.show(vec!["directive_tier_id", "name"]);
+                    join
+                }),
+                DIRECTIVE_LIMIT,
+            ),
+            fetch_character_rows(
+                rest_client,
+                character_id,
+                "characters_directive",
+                join_definitions.then(|| definition_join("directive", "directive_id")),
+                DIRECTIVE_LIMIT,
+            ),
+            fetch_character_rows(
+                rest_client,
+                character_id,
+                "characters_directive_objective",
+                None,
+                DIRECTIVE_LIMIT,
+            ),
+        )
+        .await?;
+
+        return Ok(CharacterDirectives {
+            trees: trees
+                .iter()
+                .filter_map(DirectiveTreeProgress::from_json)
+                .collect(),
+            tiers: tiers
+                .iter()
+                .filter_map(DirectiveTierProgress::from_json)
+                .collect(),
+            directives: directives
+                .iter()
+                .filter_map(DirectiveProgress::from_json)
+                .collect(),
+            objectives: objectives
+                .iter()
+                .filter_map(DirectiveObjectiveProgress::from_json)
+                .collect(),
+        });
+    }
+
+    pub fn tree(&self, tree_id: u64) -> Option<&DirectiveTreeProgress> {
+        return self.trees.iter().find(|t| t.tree_id == tree_id);
+    }
+
+    /// The trees that were started but are not completed yet
+    pub fn in_progress_trees(&self) -> impl Iterator<Item = &DirectiveTreeProgress> {
+        return self.trees.iter().filter(|t| !t.is_completed());
+    }
+
+    pub fn completed_trees(&self) -> impl Iterator<Item = &DirectiveTreeProgress> {
+        return self.trees.iter().filter(|t| t.is_completed());
+    }
+
+    /// The completed tiers of a tree
+    pub fn completed_tiers(&self, tree_id: u64) -> impl Iterator<Item = &DirectiveTierProgress> {
+        return self
+            .tiers
+            .iter()
+            .filter(move |t| t.tree_id == tree_id && t.completion_time.is_some());
+    }
+
+    /// The objective counters of a directive
+    pub fn objectives_of(
+        &self,
+        directive_id: u64,
+    ) -> impl Iterator<Item = &DirectiveObjectiveProgress> {
+        return self
+            .objectives
+            .iter()
+            .filter(move |o| o.directive_id == directive_id);
+    }
+}
+
+fn definition_join(collection: &str, on: &str) -> Join {
+    let mut join = Join::new(collection);
+    join.on(on);
+    join.inject_at(collection);
+    join.show(vec!["name"]);
+    return join;
+}
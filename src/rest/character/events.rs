@@ -0,0 +1,414 @@
+use serde_json::{Map, Value};
+
+use crate::events::api_events::event_types::ApiEvent;
+use crate::events::api_events::{
+    AchievementEarned, BattleRankUp, Death, Event, ItemAdded, PlayerFacilityCapture, VehicleDestroy,
+};
+use crate::rest::RestClient;
+use crate::shared::ids::CharacterId;
+use crate::utils::CensusError;
+
+/// The most events census returns per page
+const MAX_PAGE_SIZE: u64 = 1000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CharacterEventType {
+    Kill,
+    Death,
+    VehicleDestroy,
+    Achievement,
+    BattleRank,
+    FacilityCharacter,
+    Item,
+}
+
+impl CharacterEventType {
+    pub fn to_census_str(&self) -> &'static str {
+        match self {
+            CharacterEventType::Kill => "KILL",
+            CharacterEventType::Death => "DEATH",
+            CharacterEventType::VehicleDestroy => "VEHICLE_DESTROY",
+            CharacterEventType::Achievement => "ACHIEVEMENT",
+            CharacterEventType::BattleRank => "BATTLE_RANK",
+            CharacterEventType::FacilityCharacter => "FACILITY_CHARACTER",
+            CharacterEventType::Item => "ITEM",
+        }
+    }
+}
+
+///
+/// An event from the history of a character
+///
+/// Census leaves some fields of the websocket events out of the rest rows, they are set to 0
+/// in the event and listed in missing_fields.
+///
+#[derive(Debug)]
+pub struct CharacterEvent {
+    pub event: ApiEvent,
+    pub missing_fields: Vec<&'static str>,
+}
+
+impl CharacterEvent {
+    /// True if the field was not part of the row and is only a 0 in the event
+    pub fn is_missing(&self, field: &str) -> bool {
+        return self.missing_fields.contains(&field);
+    }
+}
+
+///
+/// Pages through the event history of a character from characters_event, newest first
+///
+/// Rows are returned as the same events the websocket sends, see CharacterEvent for the fields
+/// census leaves out. Kills and deaths are both returned as a Death event.
+/// Facility rows do not tell captures and defends apart and are returned as a PlayerFacilityCapture.
+/// Rows that can not be parsed are skipped.
+///
+#[derive(Clone, Debug)]
+pub struct CharacterEventFeed {
+    character_id: CharacterId,
+    types: Vec<CharacterEventType>,
+    after: Option<u64>,
+    before: Option<u64>,
+    page_size: u64,
+    /// The rows of the previous page at its oldest timestamp, the next page requests that second
+    /// again and leaves these rows out
+    seen_at_oldest: Vec<Value>,
+    exhausted: bool,
+}
+
+impl CharacterEventFeed {
+    pub fn new(character_id: CharacterId) -> CharacterEventFeed {
+        return CharacterEventFeed {
+            character_id,
+            types: Vec::new(),
+            after: None,
+            before: None,
+            page_size: 100,
+            seen_at_oldest: Vec::new(),
+            exhausted: false,
+        };
+    }
+
+    /// Only return events of the given types, every type is returned if none are set
+    pub fn types(&mut self, types: Vec<CharacterEventType>) {
+        self.types = types;
+    }
+
+    /// Only return events after the given unix timestamp
+    pub fn after(&mut self, timestamp: u64) {
+        self.after = Some(timestamp);
+    }
+
+    /// Only return events before the given unix timestamp
+    pub fn before(&mut self, timestamp: u64) {
+        self.before = Some(timestamp);
+    }
+
+    /// Census returns at most 1000 events per page, at least one event is requested
+    pub fn page_size(&mut self, page_size: u64) {
+        self.page_size = page_size.clamp(1, MAX_PAGE_SIZE);
+    }
+
+    pub fn is_exhausted(&self) -> bool {
+        return self.exhausted;
+    }
+
+    /// Fetches the next page of events, an empty page once every event was returned
+    pub async fn next_page(
+        &mut self,
+        rest_client: &RestClient,
+    ) -> Result<Vec<CharacterEvent>, CensusError> {
+        if self.exhausted {
+            return Ok(Vec::new());
+        }
+
+        let mut query = rest_client.get_query_builder("characters_event");
+
+        query.search("character_id".to_string(), self.character_id.to_string());
+
+        if !self.types.is_empty() {
+            let types = self
+                .types
+                .iter()
+                .map(|t| t.to_census_str())
+                .collect::<Vec<&str>>()
+                .join(",");
+
+            query.search("type".to_string(), types);
+        }
+
+        if let Some(after) = self.after {
+            query.search("after".to_string(), after.to_string());
+        }
+
+        if let Some(before) = self.before {
+            query.search("before".to_string(), before.to_string());
+        }
+
+        // the rows seen at the oldest timestamp come back and are left out again
+        let limit = (self.page_size + self.seen_at_oldest.len() as u64).min(MAX_PAGE_SIZE);
+
+        query.limit(limit);
+
+        let mut jsonval = query.get().await?;
+
+        let rows = match jsonval["characters_event_list"].take() {
+            Value::Array(rows) => rows,
+            _ => Vec::new(),
+        };
+
+        let page = advance_page(
+            rows,
+            limit,
+            std::mem::take(&mut self.seen_at_oldest),
+            self.before,
+        );
+
+        self.before = page.before;
+        self.seen_at_oldest = page.seen_at_oldest;
+        self.exhausted = page.exhausted;
+
+        let mut events = Vec::with_capacity(page.rows.len());
+
+        for row in page.rows {
+            if let Ok(Some(event)) = parse_event_row(row) {
+                events.push(event);
+            }
+        }
+
+        return Ok(events);
+    }
+}
+
+///
+/// The rows of a page that no earlier page returned and where the next page starts
+///
+#[derive(Debug, PartialEq)]
+struct PageAdvance {
+    rows: Vec<Value>,
+    before: Option<u64>,
+    seen_at_oldest: Vec<Value>,
+    exhausted: bool,
+}
+
+/// Leaves out the rows an earlier page already returned and moves the cursor past the page,
+/// limit is the amount of rows that was requested
+fn advance_page(
+    mut rows: Vec<Value>,
+    limit: u64,
+    mut seen_at_oldest: Vec<Value>,
+    before: Option<u64>,
+) -> PageAdvance {
+    let exhausted = (rows.len() as u64) < limit;
+
+    let oldest = rows.last().and_then(row_timestamp);
+
+    let at_oldest: Vec<Value> = rows
+        .iter()
+        .filter(|row| oldest.is_some() && row_timestamp(row) == oldest)
+        .cloned()
+        .collect();
+
+    rows.retain(|row| match seen_at_oldest.iter().position(|s| s == row) {
+        Some(index) => {
+            seen_at_oldest.swap_remove(index);
+            false
+        }
+        None => true,
+    });
+
+    // before excludes the given second, so the next page requests the oldest second again
+    // and leaves out the rows of it that were already returned
+    match oldest {
+        Some(oldest) if !rows.is_empty() => {
+            return PageAdvance {
+                rows,
+                before: Some(oldest + 1),
+                seen_at_oldest: at_oldest,
+                exhausted,
+            };
+        }
+        Some(oldest) => {
+            // more events in a single second than fit in a page, the rest of the second is skipped
+            return PageAdvance {
+                rows,
+                before: Some(oldest),
+                seen_at_oldest: Vec::new(),
+                exhausted,
+            };
+        }
+        None => {
+            return PageAdvance {
+                rows,
+                before,
+                seen_at_oldest: Vec::new(),
+                exhausted: true,
+            };
+        }
+    }
+}
+
+/// Maps a characters_event row to the websocket event of its table, None for unknown tables
+fn parse_event_row(row: Value) -> Result<Option<CharacterEvent>, CensusError> {
+    let table_type = row["table_type"].as_str().unwrap_or_default().to_string();
+
+    let fields: &[&'static str] = match table_type.as_str() {
+        "deaths" => &[
+            "attacker_fire_mode_id",
+            "attacker_loadout_id",
+            "attacker_vehicle_id",
+            "character_loadout_id",
+            "is_headshot",
+            "vehicle_id",
+        ],
+        "vehicle_destroy" => &[
+            "attacker_loadout_id",
+            "attacker_vehicle_id",
+            "attacker_weapon_id",
+            "facility_id",
+            "faction_id",
+        ],
+        "achievements" | "battle_rank" => &[],
+        "facility_character" => &["outfit_id"],
+        "items" => &["context", "item_count"],
+        _ => {
+            return Ok(None);
+        }
+    };
+
+    let (row, missing_fields) = with_defaults(row, fields);
+
+    let event = match table_type.as_str() {
+        "deaths" => ApiEvent::Death(Death::from_json(&row)?),
+        "vehicle_destroy" => ApiEvent::VehicleDestroy(VehicleDestroy::from_json(&row)?),
+        "achievements" => ApiEvent::AchievementEarned(AchievementEarned::from_json(&row)?),
+        "battle_rank" => ApiEvent::BattleRankUp(BattleRankUp::from_json(&row)?),
+        "facility_character" => {
+            ApiEvent::PlayerFacilityCapture(PlayerFacilityCapture::from_json(&row)?)
+        }
+        "items" => ApiEvent::ItemAdded(ItemAdded::from_json(&row)?),
+        _ => {
+            return Ok(None);
+        }
+    };
+
+    return Ok(Some(CharacterEvent {
+        event,
+        missing_fields,
+    }));
+}
+
+/// Fills the fields census leaves out of some rows with 0 and returns which ones were filled,
+/// world and zone are left out of older rows
+fn with_defaults(row: Value, fields: &[&'static str]) -> (Value, Vec<&'static str>) {
+    let mut map = match row {
+        Value::Object(map) => map,
+        _ => Map::new(),
+    };
+
+    let mut missing_fields = Vec::new();
+
+    for field in fields.iter().chain(["world_id", "zone_id"].iter()) {
+        if !map.get(*field).map(|v| v.is_string()).unwrap_or(false) {
+            map.insert(field.to_string(), Value::String("0".to_string()));
+            missing_fields.push(*field);
+        }
+    }
+
+    return (Value::Object(map), missing_fields);
+}
+
+fn row_timestamp(row: &Value) -> Option<u64> {
+    return row["timestamp"]
+        .as_str()
+        .and_then(|s| s.parse::<u64>().ok());
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use super::{advance_page, PageAdvance};
+
+    fn row(timestamp: u64, id: &str) -> Value {
+        return json!({ "timestamp": timestamp.to_string(), "character_id": id });
+    }
+
+    #[test]
+    fn ties_across_a_page_boundary() {
+        let first = advance_page(
+            vec![row(10, "a"), row(9, "b"), row(9, "c")],
+            3,
+            Vec::new(),
+            None,
+        );
+
+        assert_eq!(first.before, Some(10));
+        assert_eq!(first.seen_at_oldest, vec![row(9, "b"), row(9, "c")]);
+        assert!(!first.exhausted);
+
+        // the next page requests second 9 again with room for the rows that come back
+        let second = advance_page(
+            vec![
+                row(9, "b"),
+                row(9, "c"),
+                row(9, "d"),
+                row(8, "e"),
+                row(7, "f"),
+            ],
+            5,
+            first.seen_at_oldest,
+            first.before,
+        );
+
+        assert_eq!(
+            second,
+            PageAdvance {
+                rows: vec![row(9, "d"), row(8, "e"), row(7, "f")],
+                before: Some(8),
+                seen_at_oldest: vec![row(7, "f")],
+                exhausted: false,
+            }
+        );
+    }
+
+    #[test]
+    fn page_of_seen_rows_skips_the_second() {
+        let page = advance_page(
+            vec![row(9, "b"), row(9, "c")],
+            2,
+            vec![row(9, "b"), row(9, "c")],
+            Some(10),
+        );
+
+        assert_eq!(
+            page,
+            PageAdvance {
+                rows: Vec::new(),
+                before: Some(9),
+                seen_at_oldest: Vec::new(),
+                exhausted: false,
+            }
+        );
+    }
+
+    #[test]
+    fn short_and_empty_pages_exhaust() {
+        let short = advance_page(vec![row(9, "d")], 3, vec![row(9, "c")], Some(10));
+
+        assert_eq!(short.rows, vec![row(9, "d")]);
+        assert!(short.exhausted);
+
+        let empty = advance_page(Vec::new(), 3, vec![row(9, "c")], Some(10));
+
+        assert_eq!(
+            empty,
+            PageAdvance {
+                rows: Vec::new(),
+                before: Some(10),
+                seen_at_oldest: Vec::new(),
+                exhausted: true,
+            }
+        );
+    }
+}
@@ -0,0 +1,80 @@
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::rest::census_value::FromCensusJson;
+use crate::shared::ids::CharacterId;
+use crate::utils::{parse_nonzero_u64, parse_u64};
+
+///
+/// A single entry of a character's friends list
+///
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Friend {
+    pub character_id: CharacterId,
+    /// Only sent by census for friends that still exist
+    pub name: Option<String>,
+    pub online: bool,
+    /// Unix timestamp in seconds, None if the friend never logged in
+    pub last_online: Option<u64>,
+}
+
+impl Friend {
+    pub fn from_json(json: &Value) -> Option<Friend> {
+        let character_id = json["character_id"].as_str()?.parse::<CharacterId>().ok()?;
+
+        // census has sent both spellings of the online flag
+        let online_v = match json["online"].is_null() {
+            true => &json["onlineStatus"],
+            false => &json["online"],
+        };
+
+        return Some(Friend {
+            character_id,
+            name: json["name"]["first"].as_str().map(|s| s.to_string()),
+            online: parse_u64(online_v) != 0,
+            last_online: parse_nonzero_u64(&json["last_online"]),
+        });
+    }
+}
+
+///
+/// The friends of a character, resolved by friends
+///
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct FriendList {
+    pub friends: Vec<Friend>,
+}
+
+impl FriendList {
+    /// The friends that are currently logged in
+    pub fn online(&self) -> impl Iterator<Item = &Friend> {
+        return self.friends.iter().filter(|f| f.online);
+    }
+
+    pub fn ids(&self) -> Vec<CharacterId> {
+        return self.friends.iter().map(|f| f.character_id).collect();
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Friend> {
+        return self.friends.iter();
+    }
+
+    pub fn len(&self) -> usize {
+        return self.friends.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.friends.is_empty();
+    }
+}
+
+impl FromCensusJson for FriendList {
+    /// Parses the friend_list of a character, None if it was not resolved
+    fn from_json(json: &Value) -> Option<FriendList> {
+        let list = json.as_array()?;
+
+        return Some(FriendList {
+            friends: list.iter().filter_map(Friend::from_json).collect(),
+        });
+    }
+}
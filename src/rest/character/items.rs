@@ -0,0 +1,78 @@
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::rest::{census_value::FromCensusJson, item::Item};
+use crate::shared::ids::ItemId;
+
+///
+/// An item owned by a character
+///
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct CharacterItem {
+    pub item: Item,
+    pub stack_count: u64,
+    /// Unlocked for every character on the account
+    pub account_level: bool,
+}
+
+impl CharacterItem {
+    pub fn from_json(json: &Value) -> Option<CharacterItem> {
+        let item = Item::from_json(json)?;
+
+        let stack_count = json["stack_count"]
+            .as_str()
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or(1);
+
+        return Some(CharacterItem {
+            item,
+            stack_count,
+            account_level: json["account_level"].as_str() == Some("1"),
+        });
+    }
+}
+
+///
+/// The items of a character, resolved by item or item_full
+///
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct CharacterInventory {
+    pub items: Vec<CharacterItem>,
+}
+
+impl CharacterInventory {
+    pub fn get(&self, id: ItemId) -> Option<&CharacterItem> {
+        return self.items.iter().find(|i| i.item.id == id);
+    }
+
+    pub fn owns_item(&self, id: ItemId) -> bool {
+        return self.get(id).is_some();
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, CharacterItem> {
+        return self.items.iter();
+    }
+
+    pub fn len(&self) -> usize {
+        return self.items.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.items.is_empty();
+    }
+}
+
+impl FromCensusJson for CharacterInventory {
+    fn from_json(json: &Value) -> Option<CharacterInventory> {
+        let list = json.as_array()?;
+
+        let mut items = Vec::with_capacity(list.len());
+        for item_json in list {
+            if let Some(item) = CharacterItem::from_json(item_json) {
+                items.push(item);
+            }
+        }
+
+        return Some(CharacterInventory { items });
+    }
+}
@@ -0,0 +1,207 @@
+use std::collections::HashMap;
+
+use futures_util::future::try_join;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::rest::{item::Item, LocalisedString, RestClient};
+use crate::shared::ids::{CharacterId, ItemId, LoadoutId};
+use crate::utils::{parse_nonzero_u64, parse_optional, to_json_pretty, CensusError};
+
+use super::{fetch_character_rows, CharacterClass};
+
+/// The most rows fetched from a single characters_loadout* collection
+const LOADOUT_LIMIT: u64 = 5000;
+
+///
+/// An item equipped in a loadout, with its name once the items were fetched
+///
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct LoadoutItem {
+    pub item_id: ItemId,
+    pub name: Option<LocalisedString>,
+}
+
+impl LoadoutItem {
+    fn new(item_id: ItemId) -> LoadoutItem {
+        return LoadoutItem {
+            item_id,
+            name: None,
+        };
+    }
+}
+
+///
+/// A slot of a loadout, like the primary weapon or the suit slot
+///
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct LoadoutSlot {
+    pub slot_id: u64,
+    pub item: Option<LoadoutItem>,
+    pub attachments: Vec<LoadoutItem>,
+}
+
+impl LoadoutSlot {
+    fn from_json(json: &Value) -> Option<LoadoutSlot> {
+        // attachments are sent as a list of items or as a comma separated list of ids
+        let attachments = match &json["attachments"] {
+            Value::Array(list) => list
+                .iter()
+                .filter_map(|a| parse_nonzero_u64(&a["item_id"]))
+                .map(|id| LoadoutItem::new(ItemId::new(id)))
+                .collect(),
+            Value::String(list) => list
+                .split(',')
+                .filter_map(|id| id.trim().parse::<ItemId>().ok())
+                .map(LoadoutItem::new)
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        return Some(LoadoutSlot {
+            slot_id: parse_optional(&json["slot_id"])?,
+            item: parse_nonzero_u64(&json["item_id"]).map(|id| LoadoutItem::new(ItemId::new(id))),
+            attachments,
+        });
+    }
+}
+
+///
+/// A saved loadout of a character, from characters_loadout and characters_loadout_slot
+///
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct CharacterLoadout {
+    pub loadout_id: LoadoutId,
+    pub profile_id: u16,
+    pub class: Option<CharacterClass>,
+    pub name: Option<String>,
+    pub slots: Vec<LoadoutSlot>,
+}
+
+impl CharacterLoadout {
+    fn from_json(json: &Value) -> Option<CharacterLoadout> {
+        let profile_id = parse_optional(&json["profile_id"]).unwrap_or(0);
+
+        return Some(CharacterLoadout {
+            loadout_id: parse_optional(&json["loadout_id"])?,
+            profile_id,
+            class: CharacterClass::from_profile_id(profile_id),
+            name: json["name"].as_str().map(|s| s.to_string()),
+            slots: Vec::new(),
+        });
+    }
+
+    fn items_mut(&mut self) -> impl Iterator<Item = &mut LoadoutItem> {
+        return self
+            .slots
+            .iter_mut()
+            .flat_map(|slot| slot.item.iter_mut().chain(slot.attachments.iter_mut()));
+    }
+
+    /// The loadout as json, to share it
+    pub fn to_json(&self) -> Result<String, CensusError> {
+        return to_json_pretty(self, "loadout");
+    }
+}
+
+///
+/// Every saved loadout of a character
+///
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct CharacterLoadouts {
+    pub loadouts: Vec<CharacterLoadout>,
+}
+
+impl CharacterLoadouts {
+    /// Fetches the loadouts and their slots, optionally fetching the names of every equipped item
+    pub async fn fetch(
+        rest_client: &RestClient,
+        character_id: CharacterId,
+        fetch_item_names: bool,
+    ) -> Result<CharacterLoadouts, CensusError> {
+        let (loadout_rows, slot_rows) = try_join(
+            fetch_character_rows(
+                rest_client,
+                character_id,
+                "characters_loadout",
+                None,
+                LOADOUT_LIMIT,
+            ),
+            fetch_character_rows(
+                rest_client,
+                character_id,
+                "characters_loadout_slot",
+                None,
+                LOADOUT_LIMIT,
+            ),
+        )
+        .await?;
+
+        let mut loadouts: Vec<CharacterLoadout> = loadout_rows
+            .iter()
+            .filter_map(CharacterLoadout::from_json)
+            .collect();
+
+        for row in &slot_rows {
+            let loadout_id: Option<LoadoutId> = parse_optional(&row["loadout_id"]);
+
+            let loadout = loadouts
+                .iter_mut()
+                .find(|l| Some(l.loadout_id) == loadout_id);
+
+            if let (Some(loadout), Some(slot)) = (loadout, LoadoutSlot::from_json(row)) {
+                loadout.slots.push(slot);
+            }
+        }
+
+        for loadout in &mut loadouts {
+            loadout.slots.sort_by_key(|s| s.slot_id);
+        }
+
+        let mut loadouts = CharacterLoadouts { loadouts };
+
+        if fetch_item_names {
+            loadouts.fetch_item_names(rest_client).await?;
+        }
+
+        return Ok(loadouts);
+    }
+
+    /// Fetches the names of every equipped item and attachment in one batched lookup
+    pub async fn fetch_item_names(&mut self, rest_client: &RestClient) -> Result<(), CensusError> {
+        let mut ids: Vec<ItemId> = Vec::new();
+
+        for loadout in &mut self.loadouts {
+            ids.extend(loadout.items_mut().map(|item| item.item_id));
+        }
+
+        ids.sort();
+        ids.dedup();
+
+        let items: HashMap<ItemId, Item> = Item::fetch_many(rest_client, &ids)
+            .await?
+            .into_iter()
+            .map(|item| (item.id, item))
+            .collect();
+
+        for loadout in &mut self.loadouts {
+            for loadout_item in loadout.items_mut() {
+                if let Some(item) = items.get(&loadout_item.item_id) {
+                    loadout_item.name = item.name.clone();
+                }
+            }
+        }
+
+        return Ok(());
+    }
+
+    /// The loadouts of a single class
+    pub fn for_class(&self, class: CharacterClass) -> impl Iterator<Item = &CharacterLoadout> {
+        return self.loadouts.iter().filter(move |l| l.class == Some(class));
+    }
+
+    /// Every loadout as json, to share them
+    pub fn to_json(&self) -> Result<String, CensusError> {
+        return to_json_pretty(self, "loadouts");
+    }
+}
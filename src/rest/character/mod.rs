@@ -0,0 +1,698 @@
+use std::sync::Arc;
+
+use ps2api_derive::CensusEntity;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokio::sync::RwLock;
+
+use crate::events::api_events::event_types::ApiEvent;
+use crate::shared::ids::{CharacterId, ItemId, OutfitId, WorldId};
+use crate::shared::worlds::pc;
+use crate::utils::CensusError;
+
+use super::{
+    census_value::{CensusValue, FromCensusJson},
+    query_builder::{Join, Resolveable},
+    LocalisedString, RestClient, FETCH_BATCH_SIZE,
+};
+
+use self::achievements::CharacterAchievements;
+use self::directives::CharacterDirectives;
+use self::events::CharacterEventFeed;
+use self::friends::FriendList;
+use self::items::CharacterInventory;
+use self::loadouts::CharacterLoadouts;
+use self::online_status::OnlineStatus;
+use self::skills::CharacterSkills;
+use self::stat_history::CharacterStatHistory;
+use self::stats::CharacterStats;
+use self::weapon_stats::CharacterWeaponStats;
+
+pub mod achievements;
+pub mod compare;
+pub mod directives;
+pub mod events;
+pub mod friends;
+pub mod items;
+pub mod loadouts;
+pub mod online_status;
+pub mod search;
+pub mod skills;
+pub mod stat_history;
+pub mod stats;
+pub mod weapon_stats;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum CharacterResolves {
+    Item,
+    ItemFull,
+    Profile,
+    Faction,
+    Stat,
+    StatByFaction,
+    WeaponStat,
+    WeaponStatByFaction,
+    StatHistory,
+    OnlineStatus,
+    Friends,
+    World,
+    Outfit,
+    OutfitMember,
+    OutfitMemberExtended,
+    Currency,
+}
+
+impl Resolveable for CharacterResolves {
+    fn from_resolve_string(resolve: &str) -> Option<Self> {
+        match resolve {
+            "item" => Some(CharacterResolves::Item),
+            "item_full" => Some(CharacterResolves::ItemFull),
+            "profile" => Some(CharacterResolves::Profile),
+            "faction" => Some(CharacterResolves::Faction),
+            "stat" => Some(CharacterResolves::Stat),
+            "stat_by_faction" => Some(CharacterResolves::StatByFaction),
+            "weapon_stat" => Some(CharacterResolves::WeaponStat),
+            "weapon_stat_by_faction" => Some(CharacterResolves::WeaponStatByFaction),
+            "stat_history" => Some(CharacterResolves::StatHistory),
+            "online_status" => Some(CharacterResolves::OnlineStatus),
+            "friends" => Some(CharacterResolves::Friends),
+            "world" => Some(CharacterResolves::World),
+            "outfit" => Some(CharacterResolves::Outfit),
+            "outfit_memeber" => Some(CharacterResolves::OutfitMember),
+            "outfit_member_extended" => Some(CharacterResolves::OutfitMemberExtended),
+            "currency" => Some(CharacterResolves::Currency),
+            _ => {
+                return None;
+            }
+        }
+    }
+
+    fn to_resolve_string(&self) -> String {
+        match self {
+            CharacterResolves::Item => "item".to_string(),
+            CharacterResolves::ItemFull => "item_full".to_string(),
+            CharacterResolves::Profile => "profile".to_string(),
+            CharacterResolves::Faction => "faction".to_string(),
+            CharacterResolves::Stat => "stat".to_string(),
+            CharacterResolves::StatByFaction => "stat_by_faction".to_string(),
+            CharacterResolves::WeaponStat => "weapon_stat".to_string(),
+            CharacterResolves::WeaponStatByFaction => "weapon_stat_by_faction".to_string(),
+            CharacterResolves::StatHistory => "stat_history".to_string(),
+            CharacterResolves::OnlineStatus => "online_status".to_string(),
+            CharacterResolves::Friends => "friends".to_string(),
+            CharacterResolves::World => "world".to_string(),
+            CharacterResolves::Outfit => "outfit".to_string(),
+            CharacterResolves::OutfitMember => "outfit_member".to_string(),
+            CharacterResolves::OutfitMemberExtended => "outfit_member_extended".to_string(),
+            CharacterResolves::Currency => "currency".to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize)]
+pub enum CharacterClass {
+    Infiltrator,
+    LightAssault,
+    CombatMedic,
+    Engineer,
+    HeavyAssault,
+    Max,
+}
+
+impl CharacterClass {
+    pub fn new(name_or_id: &str) -> Option<CharacterClass> {
+        match name_or_id {
+            "infiltrator" => Some(CharacterClass::Infiltrator),
+            "max" => Some(CharacterClass::Max),
+            "engineer" => Some(CharacterClass::Engineer),
+            "light assault" => Some(CharacterClass::LightAssault),
+            "combat medic" => Some(CharacterClass::CombatMedic),
+            "heavy assault" => Some(CharacterClass::HeavyAssault),
+            "light_assault" => Some(CharacterClass::LightAssault),
+            "combat_medic" => Some(CharacterClass::CombatMedic),
+            "heavy_assault" => Some(CharacterClass::HeavyAssault),
+            "lightassault" => Some(CharacterClass::LightAssault),
+            "combatmedic" => Some(CharacterClass::CombatMedic),
+            "heavyassault" => Some(CharacterClass::HeavyAssault),
+            "1" => Some(CharacterClass::Infiltrator),
+            "3" => Some(CharacterClass::LightAssault),
+            "4" => Some(CharacterClass::CombatMedic),
+            "5" => Some(CharacterClass::Engineer),
+            "6" => Some(CharacterClass::HeavyAssault),
+            "7" => Some(CharacterClass::Max),
+            _ => None,
+        }
+    }
+    /// Maps a faction specific profile id, like the profile_id of a character, onto its class
+    ///
+    /// CharacterClass::new only understands the generic profile type ids (1-7).
+    pub fn from_profile_id(profile_id: u16) -> Option<CharacterClass> {
+        match profile_id {
+            // NC
+            2 => Some(CharacterClass::Infiltrator),
+            4 => Some(CharacterClass::LightAssault),
+            5 => Some(CharacterClass::CombatMedic),
+            6 => Some(CharacterClass::Engineer),
+            7 => Some(CharacterClass::HeavyAssault),
+            8 => Some(CharacterClass::Max),
+            // TR
+            10 => Some(CharacterClass::Infiltrator),
+            12 => Some(CharacterClass::LightAssault),
+            13 => Some(CharacterClass::CombatMedic),
+            14 => Some(CharacterClass::Engineer),
+            15 => Some(CharacterClass::HeavyAssault),
+            16 => Some(CharacterClass::Max),
+            // VS
+            17 => Some(CharacterClass::Infiltrator),
+            19 => Some(CharacterClass::LightAssault),
+            20 => Some(CharacterClass::CombatMedic),
+            21 => Some(CharacterClass::Engineer),
+            22 => Some(CharacterClass::HeavyAssault),
+            23 => Some(CharacterClass::Max),
+            // NSO
+            190 => Some(CharacterClass::Infiltrator),
+            191 => Some(CharacterClass::LightAssault),
+            192 => Some(CharacterClass::CombatMedic),
+            193 => Some(CharacterClass::Engineer),
+            194 => Some(CharacterClass::HeavyAssault),
+            252 => Some(CharacterClass::Max),
+            _ => None,
+        }
+    }
+
+    pub fn to_id(&self) -> String {
+        match self {
+            CharacterClass::Infiltrator => "1".to_string(),
+            CharacterClass::LightAssault => "3".to_string(),
+            CharacterClass::CombatMedic => "4".to_string(),
+            CharacterClass::Engineer => "5".to_string(),
+            CharacterClass::HeavyAssault => "6".to_string(),
+            CharacterClass::Max => "7".to_string(),
+        }
+    }
+}
+
+impl FromCensusJson for CharacterClass {
+    /// Parses a generic profile type id
+    fn from_json(json: &Value) -> Option<CharacterClass> {
+        return json.as_str().and_then(CharacterClass::new);
+    }
+}
+
+/// The response of single_character_by_id, fields outside of the list are ignored while parsing
+#[derive(Deserialize)]
+struct SingleCharacterResponse {
+    #[serde(default)]
+    single_character_by_id_list: Vec<Value>,
+    error: Option<Value>,
+}
+
+///
+/// Represents a character
+///
+#[derive(Clone, Debug, CensusEntity)]
+#[census(collection = "character", resolves = CharacterResolves)]
+pub struct Character {
+    #[census(client)]
+    pub owning_client: Arc<RestClient>,
+    #[census(id = "character_id")]
+    pub id: CharacterId,
+    #[census(path = "name.first")]
+    pub name: CensusValue<String>,
+    #[census(path = "faction_id")]
+    pub faction_id: CensusValue<u8>,
+    #[census(path = "head_id")]
+    pub head_id: CensusValue<String>,
+    #[census(path = "title_id")]
+    pub title_id: CensusValue<String>,
+    #[census(path = "times.creation")]
+    pub created_at: CensusValue<String>,
+    #[census(path = "times.last_save")]
+    pub last_updated: CensusValue<String>,
+    #[census(path = "times.last_login")]
+    pub last_login: CensusValue<String>,
+    #[census(path = "times.login_count")]
+    pub login_count: CensusValue<u64>,
+    #[census(path = "times.minutes_played")]
+    pub minutes_played: CensusValue<u64>,
+    #[census(path = "certs.earned_points")]
+    pub certs_earned: CensusValue<u64>,
+    #[census(path = "certs.gifted_points")]
+    pub certs_gifted: CensusValue<u64>,
+    #[census(path = "certs.spent_points")]
+    pub certs_spent: CensusValue<u64>,
+    #[census(path = "certs.available_points")]
+    pub certs_available: CensusValue<u64>,
+    #[census(path = "certs.percent_to_next")]
+    pub certs_progress: CensusValue<u8>,
+    #[census(path = "battle_rank.value")]
+    pub battle_rank: CensusValue<u8>,
+    #[census(path = "battle_rank.percent_to_next")]
+    pub battle_rank_progress: CensusValue<u8>,
+    #[census(path = "profile_id")]
+    pub profile_id: CensusValue<u8>,
+    #[census(path = "daily_ribbon.count")]
+    pub daily_ribbon_count: CensusValue<u8>,
+    #[census(path = "daily_ribbon.time")]
+    pub daily_ribbon_time: CensusValue<String>,
+    #[census(path = "prestige_level")]
+    pub is_asp: CensusValue<bool>,
+    // resolved by outfit
+    #[census(path = "outfit.outfit_id", resolve = Outfit)]
+    pub outfit_id: CensusValue<OutfitId>,
+    #[census(path = "outfit.outfit_id_merged", resolve = Outfit)]
+    pub outfit_id_merged: CensusValue<OutfitId>,
+    #[census(path = "outfit.name", resolve = Outfit)]
+    pub outfit_name: CensusValue<String>,
+    #[census(path = "outfit.alias", resolve = Outfit)]
+    pub outfit_tag: CensusValue<String>,
+    #[census(path = "outfit.member_since_date", resolve = Outfit)]
+    pub outfit_member_join_date: CensusValue<String>,
+    // resolved by item or item_full
+    #[census(path = "items", resolve = Item)]
+    pub items: CensusValue<CharacterInventory>,
+    // resolved by profile
+    #[census(path = "profile.profile_type_id", resolve = Profile)]
+    pub class: CensusValue<CharacterClass>,
+    #[census(path = "profile.name", resolve = Profile)]
+    pub profile_name: CensusValue<LocalisedString>,
+    #[census(path = "profile.image_path", resolve = Profile)]
+    pub profile_image_path: CensusValue<String>,
+    // resolved by stat or stat_by_faction
+    #[census(path = "stats", resolve = [Stat, StatByFaction])]
+    pub stats: CensusValue<CharacterStats>,
+    // resolved by weapon_stat or weapon_stat_by_faction
+    #[census(path = "stats", resolve = [WeaponStat, WeaponStatByFaction])]
+    pub weapon_stats: CensusValue<CharacterWeaponStats>,
+    // resolved by stat_history
+    #[census(path = "stats.stat_history", resolve = StatHistory)]
+    pub stat_history: CensusValue<CharacterStatHistory>,
+    // resolved by online_status
+    #[census(path = "online_status", resolve = OnlineStatus)]
+    pub online_status: CensusValue<OnlineStatus>,
+    // resolved by friends
+    #[census(path = "friend_list", resolve = Friends)]
+    pub friends: CensusValue<FriendList>,
+    // resolved by world, only set for pc worlds, world_id holds the id on every platform
+    #[census(path = "world_id", resolve = World)]
+    pub world: CensusValue<pc::Worlds>,
+    #[census(path = "world_id", resolve = World)]
+    pub world_id: CensusValue<WorldId>,
+    // resolved by currency
+    #[census(path = "currency.quantity", resolve = Currency)]
+    pub nanites: CensusValue<u64>,
+}
+
+impl Character {
+    /// Fetches the character with the given resolves, returning every field that changed
+    ///
+    /// The shared instance of the character is updated as well unless it is locked,
+    /// see EntityRegistry::merge_existing.
+    pub async fn fetch_resolves(
+        &mut self,
+        resolves: Option<Vec<CharacterResolves>>,
+    ) -> Result<Vec<CharacterChange>, CensusError> {
+        let jsonchar =
+            Character::fetch_resolves_json(&self.owning_client, &self.id, resolves).await?;
+
+        return Ok(self.update_and_merge(&jsonchar));
+    }
+
+    /// Fetches the given resolves into a shared character, the lock is only taken once the response arrived
+    pub async fn fetch_shared(
+        character: &Arc<RwLock<Self>>,
+        resolves: Option<Vec<CharacterResolves>>,
+    ) -> Result<Vec<CharacterChange>, CensusError> {
+        let (rest_client, id) = Character::client_and_id(character).await;
+
+        let jsonchar = Character::fetch_resolves_json(&rest_client, &id, resolves).await?;
+
+        return Ok(Character::update_shared(character, &rest_client, &id, &jsonchar).await);
+    }
+
+    /// Fetches every field at once from single_character_by_id, returning every field that changed
+    ///
+    /// The response holds every stat, weapon stat and item of the character, it is deserialized
+    /// while it is received, so the raw body is never held in memory as a whole.
+    /// Fails if census does not know the character. The shared instance is updated like in fetch_resolves.
+    pub async fn fetch_full(&mut self) -> Result<Vec<CharacterChange>, CensusError> {
+        let jsonchar = Character::fetch_full_json(&self.owning_client, &self.id).await?;
+
+        return Ok(self.update_and_merge(&jsonchar));
+    }
+
+    /// Fetches every field at once into a shared character, see Character::fetch_full
+    pub async fn fetch_full_shared(
+        character: &Arc<RwLock<Self>>,
+    ) -> Result<Vec<CharacterChange>, CensusError> {
+        let (rest_client, id) = Character::client_and_id(character).await;
+
+        let jsonchar = Character::fetch_full_json(&rest_client, &id).await?;
+
+        return Ok(Character::update_shared(character, &rest_client, &id, &jsonchar).await);
+    }
+
+    /// Fetches only the given fields, using the resolves and projection they need
+    ///
+    /// The shared instance is updated like in fetch_resolves.
+    pub async fn fetch_fields(
+        &mut self,
+        fields: &[CharacterField],
+    ) -> Result<Vec<CharacterChange>, CensusError> {
+        let jsonchar = Character::fetch_fields_json(&self.owning_client, &self.id, fields).await?;
+
+        return Ok(self.update_and_merge(&jsonchar));
+    }
+
+    /// Fetches only the given fields into a shared character, see Character::fetch_fields
+    pub async fn fetch_fields_shared(
+        character: &Arc<RwLock<Self>>,
+        fields: &[CharacterField],
+    ) -> Result<Vec<CharacterChange>, CensusError> {
+        let (rest_client, id) = Character::client_and_id(character).await;
+
+        let jsonchar = Character::fetch_fields_json(&rest_client, &id, fields).await?;
+
+        return Ok(Character::update_shared(character, &rest_client, &id, &jsonchar).await);
+    }
+
+    /// Creates a character and prefetches the given list of resolves, the shared instance is updated as well
+    pub async fn new_prefeched(
+        rest_client: Arc<RestClient>,
+        id: CharacterId,
+        resolves: Option<Vec<CharacterResolves>>,
+    ) -> Result<Self, CensusError> {
+        let mut char = Character::new(id, rest_client);
+
+        char.fetch_resolves(resolves).await?;
+
+        return Ok(char);
+    }
+
+    /// Looks up a character by name, ignoring case
+    ///
+    /// Names are only unique within an environment, the lookup uses the environment of the given client.
+    /// If a deleted character still holds the name, the most recently logged in character is returned.
+    /// A shared instance of the character is updated as well, unless it is locked while the response arrives.
+    pub async fn find_by_name(
+        rest_client: Arc<RestClient>,
+        name: &str,
+        resolves: Option<Vec<CharacterResolves>>,
+    ) -> Result<Option<Self>, CensusError> {
+        let mut query = rest_client.get_query_builder(Character::COLLECTION);
+
+        if let Some(resolves) = resolves {
+            for r in resolves {
+                query.resolve(&r.to_resolve_string());
+            }
+        }
+
+        query.search("name.first_lower".to_string(), name.to_lowercase());
+
+        query.sort("times.last_login", false);
+
+        query.limit(1);
+
+        let jsonval = query.get().await?;
+        let jsonchar = &jsonval["character_list"][0];
+
+        if jsonchar.is_null() {
+            return Ok(None);
+        }
+
+        rest_client.characters.merge_existing(jsonchar);
+
+        return Ok(Some(Character::from_json_value(jsonchar, rest_client)?));
+    }
+
+    /// Gets the shared instance of a character from the client's registry and fetches the given resolves into it,
+    /// None if census does not know the character
+    pub async fn new_shared(
+        rest_client: Arc<RestClient>,
+        id: CharacterId,
+        resolves: Option<Vec<CharacterResolves>>,
+    ) -> Result<Option<Arc<RwLock<Self>>>, CensusError> {
+        let jsonchar = Character::fetch_resolves_json(&rest_client, &id, resolves).await?;
+
+        if jsonchar.is_null() {
+            return Ok(None);
+        }
+
+        let (char, _) = rest_client
+            .characters
+            .merge(&jsonchar, rest_client.clone())
+            .await?;
+
+        return Ok(Some(char));
+    }
+
+    /// Applies a websocket event to the shared instance of the character it is about, if anything is holding one
+    ///
+    /// Battle rank ups update the battle rank, logins and logouts the online status, other events are ignored.
+    /// Returns None if the instance is locked, see EntityRegistry::merge_existing.
+    pub fn merge_event(rest_client: &RestClient, event: &ApiEvent) -> Option<Vec<CharacterChange>> {
+        let json = match event {
+            ApiEvent::BattleRankUp(e) => json!({
+                "character_id": e.character_id.to_string(),
+                "battle_rank": { "value": e.battle_rank.to_string() },
+            }),
+            ApiEvent::PlayerLogin(e) => json!({
+                "character_id": e.character_id.to_string(),
+                "online_status": e.world_id.to_string(),
+                "world_id": e.world_id.to_string(),
+            }),
+            ApiEvent::PlayerLogout(e) => json!({
+                "character_id": e.character_id.to_string(),
+                "online_status": "0",
+            }),
+            _ => {
+                return None;
+            }
+        };
+
+        return rest_client.characters.merge_existing(&json);
+    }
+
+    /// Fetches several characters with the given resolves, characters census does not know are left out
+    ///
+    /// Shared instances of the characters are updated as well, unless they are locked while the response arrives.
+    pub async fn fetch_many(
+        rest_client: Arc<RestClient>,
+        ids: &[CharacterId],
+        resolves: Option<Vec<CharacterResolves>>,
+    ) -> Result<Vec<Self>, CensusError> {
+        let mut chars = Vec::with_capacity(ids.len());
+
+        for batch in ids.chunks(FETCH_BATCH_SIZE) {
+            let id_list = batch
+                .iter()
+                .map(|id| id.to_string())
+                .collect::<Vec<String>>()
+                .join(",");
+
+            let mut query = rest_client.get_query_builder(Character::COLLECTION);
+
+            if let Some(resolves) = &resolves {
+                for r in resolves {
+                    query.resolve(&r.to_resolve_string());
+                }
+            }
+
+            query.search(Character::ID_FIELD.to_string(), id_list);
+
+            query.limit(batch.len() as u64);
+
+            let jsonval = query.get().await?;
+
+            if let Some(list) = jsonval["character_list"].as_array() {
+                for json in list {
+                    rest_client.characters.merge_existing(json);
+
+                    chars.push(Character::from_json_value(json, rest_client.clone())?);
+                }
+            }
+        }
+
+        return Ok(chars);
+    }
+
+    /// Fetches every friend of the character as a full character, empty if friends was never resolved
+    pub async fn fetch_friends(
+        &self,
+        resolves: Option<Vec<CharacterResolves>>,
+    ) -> Result<Vec<Self>, CensusError> {
+        let ids = match &self.friends.value {
+            Some(friends) => friends.ids(),
+            None => {
+                return Ok(Vec::new());
+            }
+        };
+
+        return Character::fetch_many(self.owning_client.clone(), &ids, resolves).await;
+    }
+
+    /// Fetches the directive progress of the character, optionally joining the directive names
+    pub async fn fetch_directives(
+        &self,
+        join_definitions: bool,
+    ) -> Result<CharacterDirectives, CensusError> {
+        return CharacterDirectives::fetch(&self.owning_client, self.id, join_definitions).await;
+    }
+
+    /// Fetches every achievement the character started or completed, with the achievement definitions
+    /// and the weapon stats the progress of weapon achievements is taken from
+    pub async fn fetch_achievements(&self) -> Result<CharacterAchievements, CensusError> {
+        return CharacterAchievements::fetch(self.owning_client.clone(), self.id).await;
+    }
+
+    /// Fetches every certification the character bought, with the skill line, category and set names
+    pub async fn fetch_skills(&self) -> Result<CharacterSkills, CensusError> {
+        return CharacterSkills::fetch(&self.owning_client, self.id).await;
+    }
+
+    /// Fetches every saved loadout of the character, optionally with the names of the equipped items
+    pub async fn fetch_loadouts(
+        &self,
+        fetch_item_names: bool,
+    ) -> Result<CharacterLoadouts, CensusError> {
+        return CharacterLoadouts::fetch(&self.owning_client, self.id, fetch_item_names).await;
+    }
+
+    /// A feed over the event history of the character, see CharacterEventFeed
+    pub fn event_feed(&self) -> CharacterEventFeed {
+        return CharacterEventFeed::new(self.id);
+    }
+
+    /// Updates this instance and the shared instance of the character, which is skipped if it is locked,
+    /// like it is when this is the shared instance
+    fn update_and_merge(&mut self, jsonchar: &Value) -> Vec<CharacterChange> {
+        self.owning_client.characters.merge_existing(jsonchar);
+
+        return self.update(jsonchar);
+    }
+
+    /// Updates a shared character and the registry's instance, if the handle is not from the registry
+    async fn update_shared(
+        character: &Arc<RwLock<Self>>,
+        rest_client: &RestClient,
+        id: &CharacterId,
+        jsonchar: &Value,
+    ) -> Vec<CharacterChange> {
+        let changes = character.write().await.update(jsonchar);
+
+        if let Some(registered) = rest_client.characters.get(id) {
+            if !Arc::ptr_eq(&registered, character) {
+                rest_client.characters.merge_existing(jsonchar);
+            }
+        }
+
+        return changes;
+    }
+
+    async fn client_and_id(character: &Arc<RwLock<Self>>) -> (Arc<RestClient>, CharacterId) {
+        let char = character.read().await;
+
+        return (char.owning_client.clone(), char.id);
+    }
+
+    /// The character object of single_character_by_id, an error if census does not know the character
+    async fn fetch_full_json(
+        rest_client: &RestClient,
+        id: &CharacterId,
+    ) -> Result<Value, CensusError> {
+        let mut query = rest_client.get_query_builder("single_character_by_id");
+
+        query.search(Character::ID_FIELD.to_string(), id.to_string());
+
+        let mut response: SingleCharacterResponse = query.get_streamed().await?;
+
+        if let Some(error) = response.error {
+            return Err(CensusError {
+                err_msg: "Census returned an error for single_character_by_id".to_string(),
+                parent_err: Some(error.to_string()),
+            });
+        }
+
+        if response.single_character_by_id_list.is_empty() {
+            return Err(CensusError {
+                err_msg: "Census does not know character ".to_string() + &id.to_string(),
+                parent_err: None,
+            });
+        }
+
+        return Ok(response.single_character_by_id_list.swap_remove(0));
+    }
+
+    async fn fetch_fields_json(
+        rest_client: &RestClient,
+        id: &CharacterId,
+        fields: &[CharacterField],
+    ) -> Result<Value, CensusError> {
+        let mut query = rest_client.get_query_builder(Character::COLLECTION);
+
+        for r in Character::resolves_for(fields) {
+            query.resolve(&r.to_resolve_string());
+        }
+
+        query.show(Character::projection(fields));
+
+        query.limit(1);
+
+        query.search(Character::ID_FIELD.to_string(), id.to_string());
+
+        let mut jsonval = query.get().await?;
+
+        return Ok(jsonval["character_list"][0].take());
+    }
+
+    /// The class the character is currently playing, falls back on the profile id if profile was not resolved
+    pub fn current_class(&self) -> Option<CharacterClass> {
+        if let Some(class) = self.class.value {
+            return Some(class);
+        }
+
+        return self
+            .profile_id
+            .value
+            .and_then(|id| CharacterClass::from_profile_id(id.into()));
+    }
+
+    /// Checks the fetched items of the character, None if items were never resolved
+    pub fn owns_item(&self, id: ItemId) -> Option<bool> {
+        return self.items.value.as_ref().map(|items| items.owns_item(id));
+    }
+
+    //pub async fn get_id(&self) -> String {
+    //
+    //}
+}
+
+/// Fetches every row of a characters_* collection that belongs to a character
+pub(crate) async fn fetch_character_rows(
+    rest_client: &RestClient,
+    character_id: CharacterId,
+    collection: &str,
+    join: Option<Join>,
+    limit: u64,
+) -> Result<Vec<Value>, CensusError> {
+    let mut query = rest_client.get_query_builder(collection);
+
+    query.search("character_id".to_string(), character_id.to_string());
+
+    if let Some(join) = join {
+        query.join(join);
+    }
+
+    query.limit(limit);
+
+    let mut jsonval = query.get().await?;
+
+    match jsonval[collection.to_string() + "_list"].take() {
+        Value::Array(list) => {
+            return Ok(list);
+        }
+        _ => {
+            return Ok(Vec::new());
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use std::collections::HashMap;
+
+use futures_util::future::try_join_all;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::rest::census_value::FromCensusJson;
+use crate::rest::{RestClient, FETCH_BATCH_SIZE};
+use crate::shared::ids::{CharacterId, WorldId};
+use crate::utils::CensusError;
+
+///
+/// Whether a character is logged in and on which world
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize)]
+pub enum OnlineStatus {
+    Offline,
+    Online(WorldId),
+}
+
+impl OnlineStatus {
+    pub fn is_online(&self) -> bool {
+        return matches!(self, OnlineStatus::Online(_));
+    }
+
+    pub fn world_id(&self) -> Option<WorldId> {
+        match self {
+            OnlineStatus::Offline => None,
+            OnlineStatus::Online(world_id) => Some(*world_id),
+        }
+    }
+
+    /// Checks the online status of many characters at once from characters_online_status,
+    /// characters census does not know are left out of the map
+    pub async fn fetch_many(
+        rest_client: &RestClient,
+        ids: &[CharacterId],
+    ) -> Result<HashMap<CharacterId, OnlineStatus>, CensusError> {
+        let batches = ids
+            .chunks(FETCH_BATCH_SIZE)
+            .map(|batch| fetch_batch(rest_client, batch));
+
+        let mut statuses = HashMap::with_capacity(ids.len());
+
+        for batch in try_join_all(batches).await? {
+            statuses.extend(batch);
+        }
+
+        return Ok(statuses);
+    }
+}
+
+impl FromCensusJson for OnlineStatus {
+    /// Census sends 0 for offline characters and the world id for online ones
+    fn from_json(json: &Value) -> Option<OnlineStatus> {
+        let world_id = json.as_str()?.parse::<u16>().ok()?;
+
+        if world_id == 0 {
+            return Some(OnlineStatus::Offline);
+        }
+
+        return Some(OnlineStatus::Online(WorldId::new(world_id)));
+    }
+}
+
+async fn fetch_batch(
+    rest_client: &RestClient,
+    batch: &[CharacterId],
+) -> Result<Vec<(CharacterId, OnlineStatus)>, CensusError> {
+    let id_list = batch
+        .iter()
+        .map(|id| id.to_string())
+        .collect::<Vec<String>>()
+        .join(",");
+
+    let mut query = rest_client.get_query_builder("characters_online_status");
+    query.search("character_id".to_string(), id_list);
+    query.limit(batch.len() as u64);
+
+    let jsonval = query.get().await?;
+
+    let mut statuses = Vec::with_capacity(batch.len());
+
+    if let Some(list) = jsonval["characters_online_status_list"].as_array() {
+        for json in list {
+            let id = json["character_id"]
+                .as_str()
+                .and_then(|s| s.parse::<CharacterId>().ok());
+
+            if let (Some(id), Some(status)) = (id, OnlineStatus::from_json(&json["online_status"]))
+            {
+                statuses.push((id, status));
+            }
+        }
+    }
+
+    return Ok(statuses);
+}
@@ -0,0 +1,147 @@
+use serde_json::Value;
+
+use crate::rest::{query_builder::Join, RestClient};
+use crate::shared::ids::{CharacterId, WorldId};
+use crate::utils::{parse_optional, CensusError};
+
+use super::Character;
+
+/// How many more characters are requested when filtering by world, census applies the limit
+/// before the world join would drop characters of other worlds
+const WORLD_FILTER_OVERFETCH: u64 = 10;
+
+/// The most characters census returns for a single query
+const MAX_LIMIT: u64 = 1000;
+
+///
+/// A character returned by a name search, only holds what is needed to pick a character
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct CharacterSearchResult {
+    pub id: CharacterId,
+    pub name: String,
+    pub faction_id: u8,
+    pub battle_rank: u8,
+    pub world_id: Option<WorldId>,
+}
+
+impl CharacterSearchResult {
+    fn from_json(json: &Value) -> Option<CharacterSearchResult> {
+        let id: CharacterId = parse_optional(&json["character_id"])?;
+        let name = json["name"]["first"].as_str()?.to_string();
+
+        let faction_id = parse_optional(&json["faction_id"]).unwrap_or(0);
+
+        let battle_rank = parse_optional(&json["battle_rank"]["value"]).unwrap_or(0);
+
+        let world_id: Option<WorldId> = parse_optional(&json["world"]["world_id"]);
+
+        return Some(CharacterSearchResult {
+            id,
+            name,
+            faction_id,
+            battle_rank,
+            world_id,
+        });
+    }
+}
+
+///
+/// Searches characters by the start of their name, meant for autocompletion
+///
+#[derive(Clone, Debug)]
+pub struct CharacterSearch {
+    prefix: String,
+    limit: u64,
+    faction_id: Option<u8>,
+    world_id: Option<WorldId>,
+}
+
+impl CharacterSearch {
+    pub fn new(prefix: &str) -> CharacterSearch {
+        return CharacterSearch {
+            prefix: prefix.to_lowercase(),
+            limit: 10,
+            faction_id: None,
+            world_id: None,
+        };
+    }
+
+    pub fn limit(&mut self, limit: u64) {
+        self.limit = limit;
+    }
+
+    /// Only return characters of the given faction
+    pub fn faction(&mut self, faction_id: u8) {
+        self.faction_id = Some(faction_id);
+    }
+
+    /// Only return characters on the given world
+    ///
+    /// The filter is applied to an over-fetched list of matches, so it is best-effort:
+    /// a short prefix matching many characters on other worlds can return fewer results than the limit.
+    pub fn world(&mut self, world_id: WorldId) {
+        self.world_id = Some(world_id);
+    }
+
+    pub async fn get(
+        &self,
+        rest_client: &RestClient,
+    ) -> Result<Vec<CharacterSearchResult>, CensusError> {
+        if self.prefix.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut query = rest_client.get_query_builder(Character::COLLECTION);
+
+        query.search(
+            "name.first_lower".to_string(),
+            "^".to_string() + &self.prefix,
+        );
+
+        if let Some(faction_id) = self.faction_id {
+            query.search("faction_id".to_string(), faction_id.to_string());
+        }
+
+        query.show(vec!["character_id", "name", "faction_id", "battle_rank"]);
+
+        let mut world = Join::new("characters_world");
+        world.on("character_id");
+        world.inject_at("world");
+        world.show(vec!["world_id"]);
+        query.join(world);
+
+        query.exact_match_first(true);
+
+        match self.world_id {
+            Some(_) => {
+                query.limit((self.limit * WORLD_FILTER_OVERFETCH).min(MAX_LIMIT));
+            }
+            None => {
+                query.limit(self.limit);
+            }
+        }
+
+        let jsonval = query.get().await?;
+
+        let mut results = Vec::new();
+
+        if let Some(list) = jsonval["character_list"].as_array() {
+            for json in list {
+                if let Some(result) = CharacterSearchResult::from_json(json) {
+                    if self.world_id.is_some() && result.world_id != self.world_id {
+                        continue;
+                    }
+
+                    results.push(result);
+                }
+            }
+        }
+
+        if self.limit > 0 {
+            results.truncate(self.limit as usize);
+        }
+
+        return Ok(results);
+    }
+}
@@ -0,0 +1,174 @@
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::rest::{query_builder::Join, LocalisedString, RestClient};
+use crate::shared::ids::{CharacterId, ItemId};
+use crate::utils::{parse_nonzero_u64, parse_optional, CensusError};
+
+/// The most rows fetched from characters_skill
+const SKILL_LIMIT: u64 = 5000;
+
+///
+/// A certification bought by a character, from characters_skill joined with the skill definitions
+///
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct CharacterSkill {
+    pub skill_id: u64,
+    pub name: Option<LocalisedString>,
+    pub line_id: Option<u64>,
+    /// The position of the skill in its line, counting from 0
+    pub line_index: Option<u64>,
+    pub line_name: Option<LocalisedString>,
+    pub category_id: Option<u64>,
+    pub category_name: Option<LocalisedString>,
+    pub set_id: Option<u64>,
+    pub set_name: Option<LocalisedString>,
+    /// The item the skill unlocks, if any
+    pub grant_item_id: Option<ItemId>,
+    /// Unix timestamp in seconds
+    pub last_save: Option<u64>,
+}
+
+impl CharacterSkill {
+    fn from_json(json: &Value) -> Option<CharacterSkill> {
+        let skill = &json["skill"];
+
+        return Some(CharacterSkill {
+            skill_id: parse_optional(&json["skill_id"])?,
+            name: LocalisedString::from_json(&skill["name"]),
+            line_id: parse_optional(&skill["skill_line_id"]),
+            line_index: parse_optional(&skill["skill_line_index"]),
+            line_name: LocalisedString::from_json(&skill["skill_line"]["name"]),
+            category_id: parse_optional(&skill["skill_category_id"]),
+            category_name: LocalisedString::from_json(&skill["skill_category"]["name"]),
+            set_id: parse_optional(&skill["skill_set_id"]),
+            set_name: LocalisedString::from_json(&skill["skill_set"]["name"]),
+            grant_item_id: parse_nonzero_u64(&skill["grant_item_id"]).map(ItemId::new),
+            last_save: parse_nonzero_u64(&json["last_save"]),
+        });
+    }
+}
+
+///
+/// How far a character got in a certification line
+///
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct SkillLineProgress {
+    pub line_id: u64,
+    pub name: Option<LocalisedString>,
+    pub category_name: Option<LocalisedString>,
+    pub set_name: Option<LocalisedString>,
+    /// The amount of ranks bought, a line at rank 5 has its first 5 skills unlocked
+    pub rank: u64,
+}
+
+///
+/// Every certification a character bought
+///
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct CharacterSkills {
+    pub skills: Vec<CharacterSkill>,
+}
+
+impl CharacterSkills {
+    /// Fetches characters_skill joined with the skill, skill_line, skill_category and skill_set definitions
+    pub async fn fetch(
+        rest_client: &RestClient,
+        character_id: CharacterId,
+    ) -> Result<CharacterSkills, CensusError> {
+        let mut query = rest_client.get_query_builder("characters_skill");
+
+        query.search("character_id".to_string(), character_id.to_string());
+
+        let mut skill = Join::new("skill");
+        skill.on("skill_id");
+        skill.inject_at("skill");
+
+        for collection in ["skill_line", "skill_category", "skill_set"] {
+            let mut definition = Join::new(collection);
+            definition.on(&(collection.to_string() + "_id"));
+            definition.inject_at(collection);
+            definition.show(vec!["name"]);
+            skill.join(definition);
+        }
+
+        query.join(skill);
+
+        query.limit(SKILL_LIMIT);
+
+        let jsonval = query.get().await?;
+
+        let mut skills = Vec::new();
+
+        if let Some(list) = jsonval["characters_skill_list"].as_array() {
+            for json in list {
+                if let Some(skill) = CharacterSkill::from_json(json) {
+                    skills.push(skill);
+                }
+            }
+        }
+
+        return Ok(CharacterSkills { skills });
+    }
+
+    pub fn has_skill(&self, skill_id: u64) -> bool {
+        return self.skills.iter().any(|s| s.skill_id == skill_id);
+    }
+
+    /// The progress of every certification line the character bought into
+    pub fn lines(&self) -> Vec<SkillLineProgress> {
+        let mut lines: Vec<SkillLineProgress> = Vec::new();
+
+        for skill in &self.skills {
+            let (line_id, line_index) = match (skill.line_id, skill.line_index) {
+                (Some(line_id), Some(line_index)) => (line_id, line_index),
+                _ => continue,
+            };
+
+            match lines.iter_mut().find(|l| l.line_id == line_id) {
+                Some(line) => {
+                    line.rank = line.rank.max(line_index + 1);
+                }
+                None => {
+                    lines.push(SkillLineProgress {
+                        line_id,
+                        name: skill.line_name.clone(),
+                        category_name: skill.category_name.clone(),
+                        set_name: skill.set_name.clone(),
+                        rank: line_index + 1,
+                    });
+                }
+            }
+        }
+
+        return lines;
+    }
+
+    /// The rank bought in a certification line, 0 if the character has none of its skills
+    pub fn line_rank(&self, line_id: u64) -> u64 {
+        return self
+            .skills
+            .iter()
+            .filter(|s| s.line_id == Some(line_id))
+            .filter_map(|s| s.line_index)
+            .map(|index| index + 1)
+            .max()
+            .unwrap_or(0);
+    }
+
+    /// Checks a certification line by its english name, ignoring case, like "Nanite Mesh Generator"
+    ///
+    /// Several lines can share a name across classes, any of them reaching the rank is enough.
+    pub fn has_line_rank(&self, line_name: &str, rank: u64) -> bool {
+        return self.lines().iter().any(|line| {
+            let name_matches = line
+                .name
+                .as_ref()
+                .and_then(|name| name.english())
+                .map(|name| name.eq_ignore_ascii_case(line_name))
+                .unwrap_or(false);
+
+            return name_matches && line.rank >= rank;
+        });
+    }
+}
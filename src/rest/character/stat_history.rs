@@ -0,0 +1,402 @@
+use std::fmt;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::rest::census_value::FromCensusJson;
+use crate::utils::parse_u64;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize)]
+pub enum HistoryStat {
+    BattleRank,
+    Certs,
+    Deaths,
+    FacilityCaptures,
+    FacilityDefends,
+    Kills,
+    Medals,
+    Score,
+    /// In seconds
+    Time,
+}
+
+impl HistoryStat {
+    pub fn from_name(name: &str) -> Option<HistoryStat> {
+        match name {
+            "battle_rank" => Some(HistoryStat::BattleRank),
+            "certs" => Some(HistoryStat::Certs),
+            "deaths" => Some(HistoryStat::Deaths),
+            "facility_capture" => Some(HistoryStat::FacilityCaptures),
+            "facility_defend" => Some(HistoryStat::FacilityDefends),
+            "kills" => Some(HistoryStat::Kills),
+            "medals" => Some(HistoryStat::Medals),
+            "score" => Some(HistoryStat::Score),
+            "time" => Some(HistoryStat::Time),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            HistoryStat::BattleRank => "battle_rank",
+            HistoryStat::Certs => "certs",
+            HistoryStat::Deaths => "deaths",
+            HistoryStat::FacilityCaptures => "facility_capture",
+            HistoryStat::FacilityDefends => "facility_defend",
+            HistoryStat::Kills => "kills",
+            HistoryStat::Medals => "medals",
+            HistoryStat::Score => "score",
+            HistoryStat::Time => "time",
+        }
+    }
+}
+
+///
+/// A calendar date in UTC, census saves stat history per UTC day
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub struct HistoryDate {
+    pub year: i32,
+    pub month: u8,
+    pub day: u8,
+}
+
+impl HistoryDate {
+    pub fn new(year: i32, month: u8, day: u8) -> HistoryDate {
+        return HistoryDate { year, month, day };
+    }
+
+    /// The date of a unix timestamp in seconds
+    pub fn from_unix(timestamp: u64) -> HistoryDate {
+        return HistoryDate::from_days((timestamp / 86400) as i64);
+    }
+
+    /// The date a number of days after 1970-01-01
+    fn from_days(days: i64) -> HistoryDate {
+        // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+        let z = days + 719468;
+        let era = z.div_euclid(146097);
+        let doe = z.rem_euclid(146097);
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u8;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u8;
+        let year = (yoe + era * 400 + if month <= 2 { 1 } else { 0 }) as i32;
+
+        return HistoryDate { year, month, day };
+    }
+
+    /// The number of days since 1970-01-01
+    fn to_days(self) -> i64 {
+        // http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+        let year = self.year as i64 - if self.month <= 2 { 1 } else { 0 };
+        let era = year.div_euclid(400);
+        let yoe = year.rem_euclid(400);
+        let month = self.month as i64;
+        let mp = if month > 2 { month - 3 } else { month + 9 };
+        let doy = (153 * mp + 2) / 5 + self.day as i64 - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+
+        return era * 146097 + doe - 719468;
+    }
+
+    pub fn add_days(&self, days: i64) -> HistoryDate {
+        return HistoryDate::from_days(self.to_days() + days);
+    }
+
+    /// The first day of the month a number of months before this date
+    pub fn months_before(&self, months: u32) -> HistoryDate {
+        let index = self.year as i64 * 12 + (self.month as i64 - 1) - months as i64;
+
+        return HistoryDate {
+            year: index.div_euclid(12) as i32,
+            month: (index.rem_euclid(12) + 1) as u8,
+            day: 1,
+        };
+    }
+
+    /// Days from this date to another, negative if the other date is earlier
+    pub fn days_until(&self, other: &HistoryDate) -> i64 {
+        return other.to_days() - self.to_days();
+    }
+}
+
+impl fmt::Display for HistoryDate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+///
+/// The value of a history bucket, dated by the first day the bucket covers
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct HistoryPoint {
+    pub date: HistoryDate,
+    pub value: u64,
+}
+
+///
+/// The history of a single stat
+///
+/// The buckets are relative to last_save: the first day is the day of the last save,
+/// the first week are the 7 days up to and including it and the first month is its calendar month.
+///
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct StatHistory {
+    pub stat: HistoryStat,
+    pub all_time: u64,
+    pub one_life_max: u64,
+    /// Unix timestamp in seconds
+    pub last_save: u64,
+    /// Newest first, 31 days
+    pub days: Vec<u64>,
+    /// Newest first, 13 weeks
+    pub weeks: Vec<u64>,
+    /// Newest first, 12 months
+    pub months: Vec<u64>,
+}
+
+impl StatHistory {
+    pub fn from_json(json: &Value) -> Option<StatHistory> {
+        let stat = HistoryStat::from_name(json["stat_name"].as_str()?)?;
+
+        return Some(StatHistory {
+            stat,
+            all_time: parse_u64(&json["all_time"]),
+            one_life_max: parse_u64(&json["one_life_max"]),
+            last_save: parse_u64(&json["last_save"]),
+            days: parse_buckets(json, "day", 'd', 31),
+            weeks: parse_buckets(json, "week", 'w', 13),
+            months: parse_buckets(json, "month", 'm', 12),
+        });
+    }
+
+    pub fn last_save_date(&self) -> HistoryDate {
+        return HistoryDate::from_unix(self.last_save);
+    }
+
+    /// Every day bucket with its date, newest first
+    pub fn days(&self) -> impl Iterator<Item = HistoryPoint> + '_ {
+        let last_save = self.last_save_date();
+
+        return self
+            .days
+            .iter()
+            .enumerate()
+            .map(move |(i, value)| HistoryPoint {
+                date: last_save.add_days(-(i as i64)),
+                value: *value,
+            });
+    }
+
+    /// Every week bucket dated by the first day of the week, newest first
+    pub fn weeks(&self) -> impl Iterator<Item = HistoryPoint> + '_ {
+        let last_save = self.last_save_date();
+
+        return self
+            .weeks
+            .iter()
+            .enumerate()
+            .map(move |(i, value)| HistoryPoint {
+                date: last_save.add_days(-(i as i64 * 7 + 6)),
+                value: *value,
+            });
+    }
+
+    /// Every month bucket dated by the first day of the month, newest first
+    pub fn months(&self) -> impl Iterator<Item = HistoryPoint> + '_ {
+        let last_save = self.last_save_date();
+
+        return self
+            .months
+            .iter()
+            .enumerate()
+            .map(move |(i, value)| HistoryPoint {
+                date: last_save.months_before(i as u32),
+                value: *value,
+            });
+    }
+
+    /// The value of a single day, None if the day is outside of the tracked 31 days
+    pub fn day(&self, date: &HistoryDate) -> Option<u64> {
+        let index = date.days_until(&self.last_save_date());
+
+        if index < 0 {
+            return None;
+        }
+
+        return self.days.get(index as usize).copied();
+    }
+
+    /// The sum of the last n days up to last_save
+    pub fn last_days(&self, n: usize) -> u64 {
+        return self.days.iter().take(n).sum();
+    }
+
+    /// The sum of the last n weeks up to last_save
+    pub fn last_weeks(&self, n: usize) -> u64 {
+        return self.weeks.iter().take(n).sum();
+    }
+
+    /// The sum of the last n months up to last_save
+    pub fn last_months(&self, n: usize) -> u64 {
+        return self.months.iter().take(n).sum();
+    }
+
+    /// The sum of every window of days ending at each tracked day, oldest first,
+    /// days where the window would reach past the tracked history are left out
+    pub fn rolling_sum(&self, window: usize) -> Vec<HistoryPoint> {
+        if window == 0 || window > self.days.len() {
+            return Vec::new();
+        }
+
+        let last_save = self.last_save_date();
+        let mut points = Vec::with_capacity(self.days.len() - window + 1);
+
+        for end in (0..=self.days.len() - window).rev() {
+            points.push(HistoryPoint {
+                date: last_save.add_days(-(end as i64)),
+                value: self.days[end..end + window].iter().sum(),
+            });
+        }
+
+        return points;
+    }
+
+    /// The daily average of every window of days, see rolling_sum
+    pub fn rolling_average(&self, window: usize) -> Vec<(HistoryDate, f64)> {
+        return self
+            .rolling_sum(window)
+            .into_iter()
+            .map(|p| (p.date, p.value as f64 / window as f64))
+            .collect();
+    }
+}
+
+///
+/// The stat history of a character, resolved by stat_history
+///
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct CharacterStatHistory {
+    pub stats: Vec<StatHistory>,
+}
+
+impl CharacterStatHistory {
+    pub fn get(&self, stat: HistoryStat) -> Option<&StatHistory> {
+        return self.stats.iter().find(|s| s.stat == stat);
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, StatHistory> {
+        return self.stats.iter();
+    }
+}
+
+impl FromCensusJson for CharacterStatHistory {
+    /// Parses the stat_history list of a character, None if it was not resolved
+    fn from_json(json: &Value) -> Option<CharacterStatHistory> {
+        let list = json.as_array()?;
+
+        return Some(CharacterStatHistory {
+            stats: list.iter().filter_map(StatHistory::from_json).collect(),
+        });
+    }
+}
+
+/// Reads the buckets of a stat, census nests them like day.d01 but flat day01 keys are read too
+fn parse_buckets(json: &Value, object: &str, prefix: char, count: usize) -> Vec<u64> {
+    let buckets = &json[object];
+
+    if buckets.is_object() {
+        return (1..=count)
+            .map(|i| parse_u64(&buckets[format!("{}{:02}", prefix, i)]))
+            .collect();
+    }
+
+    return (1..=count)
+        .map(|i| parse_u64(&json[format!("{}{:02}", object, i)]))
+        .collect();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HistoryDate;
+
+    #[test]
+    fn days_of_known_dates() {
+        assert_eq!(HistoryDate::new(1970, 1, 1).to_days(), 0);
+        assert_eq!(HistoryDate::new(1969, 12, 31).to_days(), -1);
+        assert_eq!(HistoryDate::new(2000, 3, 1).to_days(), 11017);
+        assert_eq!(
+            HistoryDate::from_unix(1_700_000_000),
+            HistoryDate::new(2023, 11, 14)
+        );
+    }
+
+    #[test]
+    fn days_round_trip() {
+        for days in -800..20_000 {
+            assert_eq!(HistoryDate::from_days(days).to_days(), days);
+        }
+    }
+
+    #[test]
+    fn leap_years() {
+        assert_eq!(
+            HistoryDate::new(2024, 2, 28).add_days(1),
+            HistoryDate::new(2024, 2, 29)
+        );
+        assert_eq!(
+            HistoryDate::new(2024, 2, 29).add_days(1),
+            HistoryDate::new(2024, 3, 1)
+        );
+        assert_eq!(
+            HistoryDate::new(2023, 2, 28).add_days(1),
+            HistoryDate::new(2023, 3, 1)
+        );
+        // divisible by 100 but not by 400
+        assert_eq!(
+            HistoryDate::new(2100, 2, 28).add_days(1),
+            HistoryDate::new(2100, 3, 1)
+        );
+        assert_eq!(
+            HistoryDate::new(2000, 2, 28).add_days(1),
+            HistoryDate::new(2000, 2, 29)
+        );
+        assert_eq!(
+            HistoryDate::new(2024, 1, 1).days_until(&HistoryDate::new(2025, 1, 1)),
+            366
+        );
+    }
+
+    #[test]
+    fn year_boundaries() {
+        assert_eq!(
+            HistoryDate::new(2023, 12, 31).add_days(1),
+            HistoryDate::new(2024, 1, 1)
+        );
+        assert_eq!(
+            HistoryDate::new(2024, 1, 1).add_days(-1),
+            HistoryDate::new(2023, 12, 31)
+        );
+        assert_eq!(
+            HistoryDate::new(2023, 12, 31).days_until(&HistoryDate::new(2023, 1, 1)),
+            -364
+        );
+    }
+
+    #[test]
+    fn months_before_across_january() {
+        let date = HistoryDate::new(2024, 1, 15);
+
+        assert_eq!(date.months_before(0), HistoryDate::new(2024, 1, 1));
+        assert_eq!(date.months_before(1), HistoryDate::new(2023, 12, 1));
+        assert_eq!(date.months_before(12), HistoryDate::new(2023, 1, 1));
+        assert_eq!(date.months_before(13), HistoryDate::new(2022, 12, 1));
+        assert_eq!(
+            HistoryDate::new(2024, 3, 31).months_before(2),
+            HistoryDate::new(2024, 1, 1)
+        );
+    }
+}
@@ -0,0 +1,302 @@
+use std::{collections::HashMap, ops::Add};
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::rest::census_value::FromCensusJson;
+use crate::utils::{parse_optional, parse_u64, per_minute, ratio};
+
+pub mod stat_names {
+    pub static KILLS: &str = "weapon_kills";
+    pub static DEATHS: &str = "weapon_deaths";
+    pub static SCORE: &str = "weapon_score";
+    /// In seconds
+    pub static PLAY_TIME: &str = "weapon_play_time";
+    pub static FACILITY_CAPTURES: &str = "facility_capture_count";
+    pub static FACILITY_DEFENDS: &str = "facility_defended_count";
+    pub static MEDALS: &str = "medal_count";
+    /// Ribbons are counted as achievements by census
+    pub static RIBBONS: &str = "achievement_count";
+    pub static ASSISTS: &str = "assist_count";
+    pub static DOMINATIONS: &str = "domination_count";
+    pub static REVENGES: &str = "revenge_count";
+    pub static CERTS: &str = "skill_points";
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize)]
+pub enum StatPeriod {
+    Forever,
+    Monthly,
+    Weekly,
+    Daily,
+    /// The best value reached in a single life
+    OneLife,
+}
+
+///
+/// The value of a stat over every period census tracks
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize)]
+pub struct StatPeriods {
+    pub forever: u64,
+    pub monthly: u64,
+    pub weekly: u64,
+    pub daily: u64,
+    pub one_life_max: u64,
+}
+
+impl StatPeriods {
+    /// Parses the value_<period><suffix> fields of a stat row
+    fn from_json(json: &Value, suffix: &str) -> StatPeriods {
+        let value = |period: &str| -> u64 {
+            return parse_u64(&json["value_".to_string() + period + suffix]);
+        };
+
+        return StatPeriods {
+            forever: value("forever"),
+            monthly: value("monthly"),
+            weekly: value("weekly"),
+            daily: value("daily"),
+            one_life_max: value("one_life_max"),
+        };
+    }
+
+    pub fn get(&self, period: StatPeriod) -> u64 {
+        match period {
+            StatPeriod::Forever => self.forever,
+            StatPeriod::Monthly => self.monthly,
+            StatPeriod::Weekly => self.weekly,
+            StatPeriod::Daily => self.daily,
+            StatPeriod::OneLife => self.one_life_max,
+        }
+    }
+}
+
+impl Add for StatPeriods {
+    type Output = StatPeriods;
+
+    fn add(self, other: StatPeriods) -> StatPeriods {
+        return StatPeriods {
+            forever: self.forever + other.forever,
+            monthly: self.monthly + other.monthly,
+            weekly: self.weekly + other.weekly,
+            daily: self.daily + other.daily,
+            // a single life can not span several profiles, so the best one is kept
+            one_life_max: self.one_life_max.max(other.one_life_max),
+        };
+    }
+}
+
+///
+/// A stat split by the faction it was earned against
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize)]
+pub struct FactionStatPeriods {
+    pub vs: StatPeriods,
+    pub nc: StatPeriods,
+    pub tr: StatPeriods,
+}
+
+impl FactionStatPeriods {
+    fn from_json(json: &Value) -> FactionStatPeriods {
+        return FactionStatPeriods {
+            vs: StatPeriods::from_json(json, "_vs"),
+            nc: StatPeriods::from_json(json, "_nc"),
+            tr: StatPeriods::from_json(json, "_tr"),
+        };
+    }
+
+    pub fn total(&self) -> StatPeriods {
+        return self.vs + self.nc + self.tr;
+    }
+}
+
+impl Add for FactionStatPeriods {
+    type Output = FactionStatPeriods;
+
+    fn add(self, other: FactionStatPeriods) -> FactionStatPeriods {
+        return FactionStatPeriods {
+            vs: self.vs + other.vs,
+            nc: self.nc + other.nc,
+            tr: self.tr + other.tr,
+        };
+    }
+}
+
+///
+/// The stats of a single profile (class), keyed by census stat name, see stat_names
+///
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct ProfileStats {
+    /// resolved by stat
+    pub stats: HashMap<String, StatPeriods>,
+    /// resolved by stat_by_faction
+    pub faction_stats: HashMap<String, FactionStatPeriods>,
+}
+
+impl ProfileStats {
+    /// A stat by name, summing the factions if census only tracks it by faction
+    pub fn stat(&self, name: &str) -> StatPeriods {
+        if let Some(stat) = self.stats.get(name) {
+            return *stat;
+        }
+
+        return self
+            .faction_stats
+            .get(name)
+            .map(|s| s.total())
+            .unwrap_or_default();
+    }
+
+    pub fn faction_stat(&self, name: &str) -> Option<FactionStatPeriods> {
+        return self.faction_stats.get(name).copied();
+    }
+
+    pub fn kills(&self, period: StatPeriod) -> u64 {
+        return self.stat(stat_names::KILLS).get(period);
+    }
+
+    pub fn deaths(&self, period: StatPeriod) -> u64 {
+        return self.stat(stat_names::DEATHS).get(period);
+    }
+
+    pub fn score(&self, period: StatPeriod) -> u64 {
+        return self.stat(stat_names::SCORE).get(period);
+    }
+
+    /// In seconds
+    pub fn play_time(&self, period: StatPeriod) -> u64 {
+        return self.stat(stat_names::PLAY_TIME).get(period);
+    }
+
+    pub fn facility_captures(&self, period: StatPeriod) -> u64 {
+        return self.stat(stat_names::FACILITY_CAPTURES).get(period);
+    }
+
+    pub fn facility_defends(&self, period: StatPeriod) -> u64 {
+        return self.stat(stat_names::FACILITY_DEFENDS).get(period);
+    }
+
+    pub fn medals(&self, period: StatPeriod) -> u64 {
+        return self.stat(stat_names::MEDALS).get(period);
+    }
+
+    pub fn ribbons(&self, period: StatPeriod) -> u64 {
+        return self.stat(stat_names::RIBBONS).get(period);
+    }
+
+    /// Kills per death, the kills if there were no deaths
+    pub fn kdr(&self, period: StatPeriod) -> f64 {
+        return ratio(self.kills(period), self.deaths(period));
+    }
+
+    /// Score per minute
+    pub fn spm(&self, period: StatPeriod) -> f64 {
+        return per_minute(self.score(period), self.play_time(period));
+    }
+
+    /// Kills per minute
+    pub fn kpm(&self, period: StatPeriod) -> f64 {
+        return per_minute(self.kills(period), self.play_time(period));
+    }
+
+    fn merge(&mut self, other: &ProfileStats) {
+        for (name, stat) in &other.stats {
+            let entry = self.stats.entry(name.clone()).or_default();
+            *entry = *entry + *stat;
+        }
+
+        for (name, stat) in &other.faction_stats {
+            let entry = self.faction_stats.entry(name.clone()).or_default();
+            *entry = *entry + *stat;
+        }
+    }
+}
+
+///
+/// The lifetime stats of a character, resolved by stat and stat_by_faction
+///
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct CharacterStats {
+    /// Keyed by profile id, stats that are not tracked per class are under profile 0
+    pub profiles: HashMap<u16, ProfileStats>,
+}
+
+impl CharacterStats {
+    pub fn profile(&self, profile_id: u16) -> Option<&ProfileStats> {
+        return self.profiles.get(&profile_id);
+    }
+
+    /// The stats of every profile added together
+    pub fn total(&self) -> ProfileStats {
+        let mut total = ProfileStats::default();
+
+        for profile in self.profiles.values() {
+            total.merge(profile);
+        }
+
+        return total;
+    }
+
+    pub fn kdr(&self, period: StatPeriod) -> f64 {
+        return self.total().kdr(period);
+    }
+
+    pub fn spm(&self, period: StatPeriod) -> f64 {
+        return self.total().spm(period);
+    }
+
+    pub fn kpm(&self, period: StatPeriod) -> f64 {
+        return self.total().kpm(period);
+    }
+}
+
+impl FromCensusJson for CharacterStats {
+    /// Parses the stats object of a character, None if neither stat nor stat_by_faction were resolved
+    fn from_json(json: &Value) -> Option<CharacterStats> {
+        return CharacterStats::merge_json(None, json);
+    }
+
+    /// Applies the resolved rows to the current stats, stats of the other resolve are kept
+    fn merge_json(current: Option<&CharacterStats>, json: &Value) -> Option<CharacterStats> {
+        let stat_list = json["stat"].as_array();
+        let faction_list = json["stat_by_faction"].as_array();
+
+        if stat_list.is_none() && faction_list.is_none() {
+            return None;
+        }
+
+        let mut stats = current.cloned().unwrap_or_default();
+
+        for row in stat_list.into_iter().flatten() {
+            if let Some((profile_id, name)) = stat_row_key(row) {
+                stats
+                    .profiles
+                    .entry(profile_id)
+                    .or_default()
+                    .stats
+                    .insert(name, StatPeriods::from_json(row, ""));
+            }
+        }
+
+        for row in faction_list.into_iter().flatten() {
+            if let Some((profile_id, name)) = stat_row_key(row) {
+                stats
+                    .profiles
+                    .entry(profile_id)
+                    .or_default()
+                    .faction_stats
+                    .insert(name, FactionStatPeriods::from_json(row));
+            }
+        }
+
+        return Some(stats);
+    }
+}
+
+fn stat_row_key(row: &Value) -> Option<(u16, String)> {
+    let name = row["stat_name"].as_str()?.to_string();
+    let profile_id = parse_optional(&row["profile_id"]).unwrap_or(0);
+
+    return Some((profile_id, name));
+}
@@ -0,0 +1,263 @@
+use std::collections::HashMap;
+
+use serde::{Serialize, Serializer};
+use serde_json::Value;
+
+use crate::rest::census_value::FromCensusJson;
+use crate::rest::item::Item;
+use crate::rest::{LocalisedString, RestClient};
+use crate::shared::ids::{ItemId, VehicleId};
+use crate::utils::{parse_u64, per_minute, ratio, CensusError};
+
+pub mod weapon_stat_names {
+    pub static KILLS: &str = "weapon_kills";
+    pub static VEHICLE_KILLS: &str = "weapon_vehicle_kills";
+    pub static HEADSHOTS: &str = "weapon_headshots";
+    pub static DEATHS: &str = "weapon_deaths";
+    pub static KILLED_BY: &str = "weapon_killed_by";
+    pub static FIRE_COUNT: &str = "weapon_fire_count";
+    pub static HIT_COUNT: &str = "weapon_hit_count";
+    /// In seconds
+    pub static PLAY_TIME: &str = "weapon_play_time";
+    pub static SCORE: &str = "weapon_score";
+    pub static DAMAGE_GIVEN: &str = "weapon_damage_given";
+    pub static DAMAGE_TAKEN: &str = "weapon_damage_taken_by";
+}
+
+///
+/// A weapon stat split by the faction it was earned against
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize)]
+pub struct FactionValues {
+    pub vs: u64,
+    pub nc: u64,
+    pub tr: u64,
+}
+
+impl FactionValues {
+    fn from_json(json: &Value) -> FactionValues {
+        return FactionValues {
+            vs: parse_u64(&json["value_vs"]),
+            nc: parse_u64(&json["value_nc"]),
+            tr: parse_u64(&json["value_tr"]),
+        };
+    }
+
+    pub fn total(&self) -> u64 {
+        return self.vs + self.nc + self.tr;
+    }
+}
+
+///
+/// The stats of a single weapon, or of a vehicle when item_id is 0
+///
+/// Census tracks a vehicle weapon separately for every vehicle it is mounted on.
+///
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct WeaponStats {
+    pub item_id: ItemId,
+    pub vehicle_id: VehicleId,
+    /// Only set once the names were fetched, see CharacterWeaponStats::fetch_names
+    pub name: Option<LocalisedString>,
+    pub kills: FactionValues,
+    pub vehicle_kills: FactionValues,
+    pub headshots: FactionValues,
+    pub deaths: u64,
+    pub killed_by: u64,
+    pub fire_count: u64,
+    pub hit_count: u64,
+    /// In seconds
+    pub play_time: u64,
+    pub score: u64,
+    pub damage_given: FactionValues,
+    pub damage_taken: FactionValues,
+}
+
+impl WeaponStats {
+    fn new(item_id: ItemId, vehicle_id: VehicleId) -> WeaponStats {
+        return WeaponStats {
+            item_id,
+            vehicle_id,
+            ..Default::default()
+        };
+    }
+
+    fn set_stat(&mut self, name: &str, row: &Value) {
+        let value = parse_u64(&row["value"]);
+
+        match name {
+            n if n == weapon_stat_names::DEATHS => self.deaths = value,
+            n if n == weapon_stat_names::KILLED_BY => self.killed_by = value,
+            n if n == weapon_stat_names::FIRE_COUNT => self.fire_count = value,
+            n if n == weapon_stat_names::HIT_COUNT => self.hit_count = value,
+            n if n == weapon_stat_names::PLAY_TIME => self.play_time = value,
+            n if n == weapon_stat_names::SCORE => self.score = value,
+            _ => {}
+        }
+    }
+
+    fn set_faction_stat(&mut self, name: &str, row: &Value) {
+        let values = FactionValues::from_json(row);
+
+        match name {
+            n if n == weapon_stat_names::KILLS => self.kills = values,
+            n if n == weapon_stat_names::VEHICLE_KILLS => self.vehicle_kills = values,
+            n if n == weapon_stat_names::HEADSHOTS => self.headshots = values,
+            n if n == weapon_stat_names::DAMAGE_GIVEN => self.damage_given = values,
+            n if n == weapon_stat_names::DAMAGE_TAKEN => self.damage_taken = values,
+            _ => {}
+        }
+    }
+
+    /// Hits per shot fired, between 0 and 1
+    pub fn accuracy(&self) -> f64 {
+        if self.fire_count == 0 {
+            return 0_f64;
+        }
+
+        return self.hit_count as f64 / self.fire_count as f64;
+    }
+
+    /// Headshots per kill, between 0 and 1
+    pub fn headshot_rate(&self) -> f64 {
+        let kills = self.kills.total();
+
+        if kills == 0 {
+            return 0_f64;
+        }
+
+        return self.headshots.total() as f64 / kills as f64;
+    }
+
+    /// Kills per minute
+    pub fn kpm(&self) -> f64 {
+        return per_minute(self.kills.total(), self.play_time);
+    }
+
+    /// Kills per death while using the weapon, the kills if there were no deaths
+    pub fn kdr(&self) -> f64 {
+        return ratio(self.kills.total(), self.deaths);
+    }
+}
+
+///
+/// The per weapon and per vehicle stats of a character, resolved by weapon_stat and weapon_stat_by_faction
+///
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct CharacterWeaponStats {
+    /// Serialized as a list of the weapon stats
+    #[serde(serialize_with = "serialize_weapons")]
+    pub weapons: HashMap<(ItemId, VehicleId), WeaponStats>,
+}
+
+impl CharacterWeaponStats {
+    fn row_entry(&mut self, row: &Value) -> Option<(String, &mut WeaponStats)> {
+        let name = row["stat_name"].as_str()?.to_string();
+        let item_id = row["item_id"].as_str()?.parse::<ItemId>().ok()?;
+        let vehicle_id = row["vehicle_id"]
+            .as_str()
+            .and_then(|s| s.parse::<VehicleId>().ok())
+            .unwrap_or_default();
+
+        let weapon = self
+            .weapons
+            .entry((item_id, vehicle_id))
+            .or_insert_with(|| WeaponStats::new(item_id, vehicle_id));
+
+        return Some((name, weapon));
+    }
+
+    /// The stats of a weapon used outside of a vehicle
+    pub fn get(&self, item_id: ItemId) -> Option<&WeaponStats> {
+        return self.get_on_vehicle(item_id, VehicleId::default());
+    }
+
+    /// The stats of a weapon mounted on the given vehicle
+    pub fn get_on_vehicle(&self, item_id: ItemId, vehicle_id: VehicleId) -> Option<&WeaponStats> {
+        return self.weapons.get(&(item_id, vehicle_id));
+    }
+
+    /// Every weapon and vehicle in no particular order
+    pub fn iter(&self) -> std::collections::hash_map::Values<'_, (ItemId, VehicleId), WeaponStats> {
+        return self.weapons.values();
+    }
+
+    pub fn len(&self) -> usize {
+        return self.weapons.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.weapons.is_empty();
+    }
+
+    /// Fetches the names of every weapon from the item collection
+    pub async fn fetch_names(&mut self, rest_client: &RestClient) -> Result<(), CensusError> {
+        let mut ids: Vec<ItemId> = self
+            .weapons
+            .keys()
+            .map(|(item_id, _)| *item_id)
+            // item 0 holds the stats of the vehicle itself
+            .filter(|item_id| item_id.value() != 0)
+            .collect();
+
+        ids.sort();
+        ids.dedup();
+
+        let items: HashMap<ItemId, Item> = Item::fetch_many(rest_client, &ids)
+            .await?
+            .into_iter()
+            .map(|item| (item.id, item))
+            .collect();
+
+        for weapon in self.weapons.values_mut() {
+            if let Some(item) = items.get(&weapon.item_id) {
+                weapon.name = item.name.clone();
+            }
+        }
+
+        return Ok(());
+    }
+}
+
+impl FromCensusJson for CharacterWeaponStats {
+    /// Parses the stats object of a character, None if neither weapon_stat nor weapon_stat_by_faction were resolved
+    fn from_json(json: &Value) -> Option<CharacterWeaponStats> {
+        return CharacterWeaponStats::merge_json(None, json);
+    }
+
+    /// Applies the resolved rows to the current stats, weapons keep their names and the values of the other resolve
+    fn merge_json(
+        current: Option<&CharacterWeaponStats>,
+        json: &Value,
+    ) -> Option<CharacterWeaponStats> {
+        let stat_list = json["weapon_stat"].as_array();
+        let faction_list = json["weapon_stat_by_faction"].as_array();
+
+        if stat_list.is_none() && faction_list.is_none() {
+            return None;
+        }
+
+        let mut stats = current.cloned().unwrap_or_default();
+
+        for row in stat_list.into_iter().flatten() {
+            if let Some((name, weapon)) = stats.row_entry(row) {
+                weapon.set_stat(&name, row);
+            }
+        }
+
+        for row in faction_list.into_iter().flatten() {
+            if let Some((name, weapon)) = stats.row_entry(row) {
+                weapon.set_faction_stat(&name, row);
+            }
+        }
+
+        return Some(stats);
+    }
+}
+
+fn serialize_weapons<S: Serializer>(
+    weapons: &HashMap<(ItemId, VehicleId), WeaponStats>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    return serializer.collect_seq(weapons.values());
+}
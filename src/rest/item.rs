@@ -1,10 +1,91 @@
-use std::sync::Arc;
+use serde::Serialize;
+use serde_json::Value;
 
-use super::RestClient;
+use crate::shared::ids::ItemId;
+use crate::utils::CensusError;
 
-#[derive(Clone)]
+use super::{LocalisedString, RestClient, FETCH_BATCH_SIZE};
+
+///
+/// Represents an item definition
+///
+/// Only the id is guaranteed, the rest is filled in when the item was fetched with its details (like item_full)
+///
+#[derive(Clone, Debug, PartialEq, Serialize)]
 pub struct Item {
-    owning_client: Arc<RestClient>,
-    pub id: u128,
-    stack_count: u64,
+    pub id: ItemId,
+    pub name: Option<LocalisedString>,
+    pub description: Option<LocalisedString>,
+    pub item_type_id: Option<u64>,
+    pub item_category_id: Option<u64>,
+    pub faction_id: Option<u8>,
+    pub is_vehicle_weapon: Option<bool>,
+    pub image_path: Option<String>,
+}
+
+impl Item {
+    pub const COLLECTION: &'static str = "item";
+
+    pub fn new(id: ItemId) -> Self {
+        return Item {
+            id,
+            name: None,
+            description: None,
+            item_type_id: None,
+            item_category_id: None,
+            faction_id: None,
+            is_vehicle_weapon: None,
+            image_path: None,
+        };
+    }
+
+    /// Parses an item json object, returns None if it has no item id
+    pub fn from_json(json: &Value) -> Option<Item> {
+        let id = json["item_id"].as_str()?.parse::<ItemId>().ok()?;
+
+        return Some(Item {
+            id,
+            name: LocalisedString::from_json(&json["name"]),
+            description: LocalisedString::from_json(&json["description"]),
+            item_type_id: json["item_type_id"].as_str().and_then(|s| s.parse().ok()),
+            item_category_id: json["item_category_id"]
+                .as_str()
+                .and_then(|s| s.parse().ok()),
+            faction_id: json["faction_id"].as_str().and_then(|s| s.parse().ok()),
+            is_vehicle_weapon: json["is_vehicle_weapon"].as_str().map(|s| s == "1"),
+            image_path: json["image_path"].as_str().map(|s| s.to_string()),
+        });
+    }
+
+    /// Fetches the definitions of several items, ids census does not know are left out
+    pub async fn fetch_many(
+        rest_client: &RestClient,
+        ids: &[ItemId],
+    ) -> Result<Vec<Item>, CensusError> {
+        let mut items = Vec::with_capacity(ids.len());
+
+        for batch in ids.chunks(FETCH_BATCH_SIZE) {
+            let id_list = batch
+                .iter()
+                .map(|id| id.to_string())
+                .collect::<Vec<String>>()
+                .join(",");
+
+            let mut query = rest_client.get_query_builder(Item::COLLECTION);
+            query.search("item_id".to_string(), id_list);
+            query.limit(batch.len() as u64);
+
+            let jsonval = query.get().await?;
+
+            if let Some(list) = jsonval["item_list"].as_array() {
+                for json in list {
+                    if let Some(item) = Item::from_json(json) {
+                        items.push(item);
+                    }
+                }
+            }
+        }
+
+        return Ok(items);
+    }
 }
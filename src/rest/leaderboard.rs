@@ -0,0 +1,184 @@
+use std::sync::Arc;
+
+use serde_json::Value;
+
+use crate::shared::ids::{CharacterId, WorldId};
+use crate::utils::{parse_optional, CensusError};
+
+use super::{
+    character::{stats::StatPeriod, Character},
+    query_builder::Join,
+    RestClient,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LeaderboardStat {
+    Kills,
+    Score,
+    /// In seconds
+    Time,
+    Deaths,
+}
+
+impl LeaderboardStat {
+    pub fn to_census_str(&self) -> &'static str {
+        match self {
+            LeaderboardStat::Kills => "Kills",
+            LeaderboardStat::Score => "Score",
+            LeaderboardStat::Time => "Time",
+            LeaderboardStat::Deaths => "Deaths",
+        }
+    }
+}
+
+fn period_census_str(period: StatPeriod) -> &'static str {
+    match period {
+        StatPeriod::Forever => "Forever",
+        StatPeriod::Monthly => "Monthly",
+        StatPeriod::Weekly => "Weekly",
+        StatPeriod::Daily => "Daily",
+        StatPeriod::OneLife => "OneLife",
+    }
+}
+
+///
+/// A ranked character of a leaderboard
+///
+#[derive(Clone, Debug)]
+pub struct LeaderboardEntry {
+    pub rank: u64,
+    pub character_id: CharacterId,
+    pub value: u64,
+    pub world_id: Option<WorldId>,
+    /// Only set when the characters were joined
+    pub character: Option<Character>,
+}
+
+impl LeaderboardEntry {
+    fn from_json(json: &Value, rest_client: &Arc<RestClient>) -> Option<LeaderboardEntry> {
+        let character = match json["character"].is_object() {
+            true => Character::from_json_value(&json["character"], rest_client.clone()).ok(),
+            false => None,
+        };
+
+        return Some(LeaderboardEntry {
+            rank: parse_optional(&json["rank"])?,
+            character_id: parse_optional(&json["character_id"])?,
+            value: parse_optional(&json["value"]).unwrap_or(0),
+            world_id: parse_optional(&json["world"]),
+            character,
+        });
+    }
+}
+
+///
+/// A query of characters_leaderboard
+///
+#[derive(Clone, Debug)]
+pub struct Leaderboard {
+    stat: LeaderboardStat,
+    period: StatPeriod,
+    world_id: Option<WorldId>,
+    start: u64,
+    limit: u64,
+    join_characters: bool,
+}
+
+impl Leaderboard {
+    pub fn new(stat: LeaderboardStat, period: StatPeriod) -> Leaderboard {
+        return Leaderboard {
+            stat,
+            period,
+            world_id: None,
+            start: 0,
+            limit: 100,
+            join_characters: false,
+        };
+    }
+
+    /// Only rank characters of the given world
+    pub fn world(&mut self, world_id: WorldId) {
+        self.world_id = Some(world_id);
+    }
+
+    /// The rank to start at, counting from 0
+    pub fn start(&mut self, start: u64) {
+        self.start = start;
+    }
+
+    /// At least one entry is requested, so next_page always moves on
+    pub fn limit(&mut self, limit: u64) {
+        self.limit = limit.max(1);
+    }
+
+    /// Joins the ranked characters into the same request
+    pub fn join_characters(&mut self, join_characters: bool) {
+        self.join_characters = join_characters;
+    }
+
+    /// Moves to the next page of the same size
+    pub fn next_page(&mut self) {
+        self.start += self.limit;
+    }
+
+    pub async fn get(
+        &self,
+        rest_client: Arc<RestClient>,
+    ) -> Result<Vec<LeaderboardEntry>, CensusError> {
+        let mut query = rest_client.get_query_builder("characters_leaderboard");
+
+        query.search("name".to_string(), self.stat.to_census_str().to_string());
+        query.search(
+            "period".to_string(),
+            period_census_str(self.period).to_string(),
+        );
+
+        if let Some(world_id) = self.world_id {
+            query.search("world".to_string(), world_id.to_string());
+        }
+
+        if self.join_characters {
+            let mut character = Join::new(Character::COLLECTION);
+            character.on(Character::ID_FIELD);
+            character.inject_at("character");
+            query.join(character);
+        }
+
+        query.start(self.start);
+        query.limit(self.limit);
+
+        let jsonval = query.get().await?;
+
+        let mut entries = Vec::new();
+
+        if let Some(list) = jsonval["characters_leaderboard_list"].as_array() {
+            for json in list {
+                if json["character"].is_object() {
+                    rest_client.characters.merge_existing(&json["character"]);
+                }
+
+                if let Some(entry) = LeaderboardEntry::from_json(json, &rest_client) {
+                    entries.push(entry);
+                }
+            }
+        }
+
+        return Ok(entries);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Leaderboard, LeaderboardStat};
+    use crate::rest::character::stats::StatPeriod;
+
+    #[test]
+    fn next_page_advances_with_a_zero_limit() {
+        let mut leaderboard = Leaderboard::new(LeaderboardStat::Kills, StatPeriod::Weekly);
+
+        leaderboard.limit(0);
+        leaderboard.next_page();
+
+        assert_eq!(leaderboard.start, 1);
+    }
+}
@@ -1,18 +1,35 @@
-use std::{sync::Arc, time::SystemTime};
+use std::sync::Arc;
 
-use num_traits::ToPrimitive;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use self::{character::Character, registry::EntityRegistry};
+
 pub const CENSUS_URL: &str = "https://census.daybreakgames.com/";
 
+/// The amount of ids requested per census query when fetching several entities, longer id lists make the url too long
+pub(crate) const FETCH_BATCH_SIZE: usize = 100;
+
+pub mod environments {
+    pub static PC: &str = "ps2:v2";
+    pub static PS4_US: &str = "ps2ps4us:v2";
+    pub static PS4_EU: &str = "ps2ps4eu:v2";
+}
+
 pub mod api;
 pub mod census_value;
 pub mod character;
 pub mod achievement;
 pub mod item;
+pub mod leaderboard;
 pub mod outfit;
 pub mod query_builder;
+pub mod registry;
 
+///
+/// A string census returns in every language, like `{"en": "...", "de": "..."}`
+///
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
 pub struct LocalisedString {
     pub de: Option<String>,
     pub en: Option<String>,
@@ -22,27 +39,87 @@ pub struct LocalisedString {
     pub tr: Option<String>,
 }
 
+impl LocalisedString {
+    /// Parses a localised json object, returns None if it contains no known language
+    pub fn from_json(json: &Value) -> Option<LocalisedString> {
+        let lang = |key: &str| json[key].as_str().map(|s| s.to_string());
+
+        let string = LocalisedString {
+            de: lang(api::langs::GERMAN),
+            en: lang(api::langs::ENGLISH),
+            es: lang(api::langs::SPANISH),
+            fr: lang(api::langs::FRENCH),
+            it: lang(api::langs::ITALIAN),
+            tr: lang(api::langs::TURKISH),
+        };
+
+        if string == LocalisedString::default() {
+            return None;
+        }
+
+        return Some(string);
+    }
+
+    /// The string in exactly the given language
+    pub fn get_exact(&self, lang: &str) -> Option<&str> {
+        let value = match lang {
+            api::langs::GERMAN => &self.de,
+            api::langs::ENGLISH => &self.en,
+            api::langs::SPANISH => &self.es,
+            api::langs::FRENCH => &self.fr,
+            api::langs::ITALIAN => &self.it,
+            api::langs::TURKISH => &self.tr,
+            _ => &None,
+        };
+
+        return value.as_deref();
+    }
+
+    /// The string in the given language, falling back to english if it is not translated
+    pub fn get(&self, lang: &str) -> Option<&str> {
+        return self.get_exact(lang).or(self.en.as_deref());
+    }
+
+    pub fn english(&self) -> Option<&str> {
+        return self.en.as_deref();
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct RestClient {
     pub endpoint: String,
     pub(crate) serviceid: String,
     pub reqwest_client: Arc<reqwest::Client>,
+    /// Shared character instances, see Character::new_shared
+    pub characters: Arc<EntityRegistry<Character>>,
 }
 
 impl RestClient {
     pub fn new(serviceid: String) -> RestClient {
         return RestClient {
-            endpoint: String::from("ps2:v2"),
-            serviceid: serviceid,
+            endpoint: environments::PC.to_string(),
+            serviceid,
+            reqwest_client: Arc::new(reqwest::Client::new()),
+            characters: Arc::new(EntityRegistry::new()),
+        };
+    }
+
+    /// Creates a client for another environment, see rest::environments
+    pub fn new_with_environment(serviceid: String, environment: &str) -> RestClient {
+        return RestClient {
+            endpoint: environment.to_string(),
+            serviceid,
             reqwest_client: Arc::new(reqwest::Client::new()),
+            characters: Arc::new(EntityRegistry::new()),
         };
     }
 
     pub fn new_with_reqwest(serviceid: String, reqwest_client: Arc<reqwest::Client>) -> RestClient {
         return RestClient {
-            endpoint: String::from("ps2:v2"),
-            serviceid: serviceid,
-            reqwest_client: reqwest_client,
+            endpoint: environments::PC.to_string(),
+            serviceid,
+            reqwest_client,
+            characters: Arc::new(EntityRegistry::new()),
         };
     }
 
@@ -0,0 +1,186 @@
+use std::{sync::Arc, time::SystemTime};
+
+use ps2api_derive::CensusEntity;
+
+use crate::shared::ids::{CharacterId, OutfitId};
+use crate::utils::CensusError;
+
+use super::{census_value::CensusValue, query_builder::Resolveable, RestClient};
+
+use self::roster::{OutfitRoster, OutfitRosterPager};
+
+pub mod roster;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum OutfitResolves {
+    Leader,
+    LeaderName,
+    LeadersStatHistory,
+    Member,
+    Rank,
+    Membercharacter,
+    MemberCharacterName,
+    MembercharactersStatHistory,
+    MemberOnlineStatus,
+}
+
+impl Resolveable for OutfitResolves {
+    fn from_resolve_string(resolve: &str) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        match resolve {
+            "leader" => Some(OutfitResolves::Leader),
+            "leader_name" => Some(OutfitResolves::LeaderName),
+            "leaders_stat_history" => Some(OutfitResolves::LeadersStatHistory),
+            "member" => Some(OutfitResolves::Member),
+            "rank" => Some(OutfitResolves::Rank),
+            "member_character" => Some(OutfitResolves::Membercharacter),
+            "member_character_name" => Some(OutfitResolves::MemberCharacterName),
+            "member_characters_stat_history" => Some(OutfitResolves::MembercharactersStatHistory),
+            "member_online_status" => Some(OutfitResolves::MemberOnlineStatus),
+            _ => None,
+        }
+    }
+
+    fn to_resolve_string(&self) -> String {
+        match self {
+            OutfitResolves::Leader => "leader".to_string(),
+            OutfitResolves::LeaderName => "leader_name".to_string(),
+            OutfitResolves::LeadersStatHistory => "leaders_stat_history".to_string(),
+            OutfitResolves::Member => "member".to_string(),
+            OutfitResolves::Rank => "rank".to_string(),
+            OutfitResolves::Membercharacter => "member_character".to_string(),
+            OutfitResolves::MemberCharacterName => "member_character_name".to_string(),
+            OutfitResolves::MembercharactersStatHistory => {
+                "member_characters_stat_history".to_string()
+            }
+            OutfitResolves::MemberOnlineStatus => "member_online_status".to_string(),
+        }
+    }
+}
+
+///
+/// Represents an outfit
+///
+#[derive(Clone, Debug, CensusEntity)]
+#[census(collection = "outfit", resolves = OutfitResolves)]
+pub struct Outfit {
+    #[census(client)]
+    pub owning_client: Arc<RestClient>,
+    // resolved by default
+    #[census(id = "outfit_id")]
+    pub id: OutfitId,
+    #[census(path = "name")]
+    pub name: CensusValue<String>,
+    #[census(path = "alias")]
+    pub tag: CensusValue<String>,
+    #[census(path = "time_created")]
+    pub created_at: CensusValue<SystemTime>,
+    #[census(path = "leader_character_id")]
+    pub leader_character_id: CensusValue<CharacterId>,
+    #[census(path = "member_count")]
+    pub member_count: CensusValue<u64>,
+    // resolved by leader_name
+    #[census(path = "leader.name.first", resolve = LeaderName)]
+    pub leader_name: CensusValue<String>,
+}
+
+impl Outfit {
+    /// Fetches the outfit with the given resolves, returning every field that changed
+    pub async fn fetch_resolves(
+        &mut self,
+        resolves: Option<Vec<OutfitResolves>>,
+    ) -> Result<Vec<OutfitChange>, CensusError> {
+        let jsonoutfit =
+            Outfit::fetch_resolves_json(&self.owning_client, &self.id, resolves).await?;
+
+        return Ok(self.update(&jsonoutfit));
+    }
+
+    /// Creates an outfit and prefetches the given list of resolves
+    pub async fn new_prefeched(
+        rest_client: Arc<RestClient>,
+        id: OutfitId,
+        resolves: Option<Vec<OutfitResolves>>,
+    ) -> Result<Self, CensusError> {
+        let mut outfit = Outfit::new(id, rest_client);
+
+        outfit.fetch_resolves(resolves).await?;
+
+        return Ok(outfit);
+    }
+
+    /// Looks up an outfit by its tag, ignoring case
+    pub async fn find_by_tag(
+        rest_client: Arc<RestClient>,
+        tag: &str,
+        resolves: Option<Vec<OutfitResolves>>,
+    ) -> Result<Option<Self>, CensusError> {
+        return Outfit::find_by(rest_client, "alias_lower", &tag.to_lowercase(), resolves).await;
+    }
+
+    /// Looks up an outfit by its name, ignoring case
+    pub async fn find_by_name(
+        rest_client: Arc<RestClient>,
+        name: &str,
+        resolves: Option<Vec<OutfitResolves>>,
+    ) -> Result<Option<Self>, CensusError> {
+        return Outfit::find_by(rest_client, "name_lower", &name.to_lowercase(), resolves).await;
+    }
+
+    async fn find_by(
+        rest_client: Arc<RestClient>,
+        field: &str,
+        value: &str,
+        resolves: Option<Vec<OutfitResolves>>,
+    ) -> Result<Option<Self>, CensusError> {
+        let mut query = rest_client.get_query_builder(Outfit::COLLECTION);
+
+        if let Some(resolves) = resolves {
+            for r in resolves {
+                query.resolve(&r.to_resolve_string());
+            }
+        }
+
+        query.search(field.to_string(), value.to_string());
+
+        query.limit(1);
+
+        let jsonval = query.get().await?;
+        let jsonoutfit = &jsonval["outfit_list"][0];
+
+        if jsonoutfit.is_null() {
+            return Ok(None);
+        }
+
+        return Ok(Some(Outfit::from_json_value(jsonoutfit, rest_client)?));
+    }
+
+    /// Fetches every member with their rank, battle rank and online status in a single request
+    ///
+    /// Large outfits are better fetched page by page, see Outfit::roster_pager.
+    pub async fn fetch_roster(&self) -> Result<OutfitRoster, CensusError> {
+        let query = Outfit::resolves_query(
+            &self.owning_client,
+            &self.id,
+            Some(OutfitRoster::resolves()),
+        );
+
+        let jsonval = query.get().await?;
+        let jsonoutfit = &jsonval["outfit_list"][0];
+
+        if let Some(members) = jsonoutfit["members"].as_array() {
+            for member in members {
+                self.owning_client.characters.merge_existing(member);
+            }
+        }
+
+        return Ok(OutfitRoster::from_json(jsonoutfit));
+    }
+
+    /// A pager over the members of the outfit, see OutfitRosterPager
+    pub fn roster_pager(&self) -> OutfitRosterPager {
+        return OutfitRosterPager::new(self.id);
+    }
+}
@@ -0,0 +1,293 @@
+use std::sync::Arc;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::rest::census_value::FromCensusJson;
+use crate::rest::character::online_status::OnlineStatus;
+use crate::rest::character::{Character, CharacterResolves};
+use crate::rest::{query_builder::Join, RestClient};
+use crate::shared::ids::{CharacterId, OutfitId};
+use crate::utils::{parse_nonzero_u64, CensusError};
+
+use super::OutfitResolves;
+
+///
+/// A rank of an outfit, ordinal 1 is the leader rank
+///
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct OutfitRank {
+    pub ordinal: u8,
+    pub name: Option<String>,
+    pub description: Option<String>,
+}
+
+impl OutfitRank {
+    fn from_json(json: &Value) -> Option<OutfitRank> {
+        return Some(OutfitRank {
+            ordinal: json["ordinal"].as_str()?.parse().ok()?,
+            name: json["name"].as_str().map(|s| s.to_string()),
+            description: json["description"].as_str().map(|s| s.to_string()),
+        });
+    }
+}
+
+///
+/// A member of an outfit with the character details needed for a roster
+///
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct OutfitMember {
+    pub character_id: CharacterId,
+    /// None for deleted characters
+    pub name: Option<String>,
+    pub rank_name: Option<String>,
+    pub rank_ordinal: Option<u8>,
+    /// Unix timestamp in seconds
+    pub member_since: Option<u64>,
+    pub battle_rank: Option<u8>,
+    pub online_status: Option<OnlineStatus>,
+}
+
+impl OutfitMember {
+    /// Parses a member row, the character and online status are merged into the row
+    /// by the outfit resolves and injected next to it by the roster joins
+    fn from_json(member: &Value, character: &Value, online_status: &Value) -> Option<OutfitMember> {
+        return Some(OutfitMember {
+            character_id: member["character_id"].as_str()?.parse().ok()?,
+            name: character["name"]["first"].as_str().map(|s| s.to_string()),
+            rank_name: member["rank"].as_str().map(|s| s.to_string()),
+            rank_ordinal: member["rank_ordinal"].as_str().and_then(|s| s.parse().ok()),
+            member_since: parse_nonzero_u64(&member["member_since"]),
+            battle_rank: character["battle_rank"]["value"]
+                .as_str()
+                .and_then(|s| s.parse().ok()),
+            online_status: OnlineStatus::from_json(online_status),
+        });
+    }
+
+    pub fn is_online(&self) -> bool {
+        return self
+            .online_status
+            .map(|status| status.is_online())
+            .unwrap_or(false);
+    }
+}
+
+///
+/// Every member of an outfit with the ranks of the outfit
+///
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct OutfitRoster {
+    pub ranks: Vec<OutfitRank>,
+    pub members: Vec<OutfitMember>,
+}
+
+impl OutfitRoster {
+    /// The outfit resolves the roster is built from
+    pub fn resolves() -> Vec<OutfitResolves> {
+        return vec![
+            OutfitResolves::Member,
+            OutfitResolves::Rank,
+            OutfitResolves::Membercharacter,
+            OutfitResolves::MemberOnlineStatus,
+        ];
+    }
+
+    /// Parses the members and ranks of an outfit fetched with OutfitRoster::resolves
+    pub fn from_json(json: &Value) -> OutfitRoster {
+        let ranks: Vec<OutfitRank> = match json["ranks"].as_array() {
+            Some(list) => list.iter().filter_map(OutfitRank::from_json).collect(),
+            None => Vec::new(),
+        };
+
+        let members = match json["members"].as_array() {
+            Some(list) => list
+                .iter()
+                .filter_map(|m| OutfitMember::from_json(m, m, &m["online_status"]))
+                .collect(),
+            None => Vec::new(),
+        };
+
+        let mut roster = OutfitRoster { ranks, members };
+
+        roster.fill_rank_names();
+
+        return roster;
+    }
+
+    /// Members without a rank name get the name of the rank with their ordinal
+    fn fill_rank_names(&mut self) {
+        for member in &mut self.members {
+            if member.rank_name.is_some() {
+                continue;
+            }
+
+            member.rank_name = member
+                .rank_ordinal
+                .and_then(|ordinal| self.ranks.iter().find(|r| r.ordinal == ordinal))
+                .and_then(|rank| rank.name.clone());
+        }
+    }
+
+    pub fn rank(&self, ordinal: u8) -> Option<&OutfitRank> {
+        return self.ranks.iter().find(|r| r.ordinal == ordinal);
+    }
+
+    /// The members holding the rank with the given ordinal
+    pub fn with_rank(&self, ordinal: u8) -> impl Iterator<Item = &OutfitMember> {
+        return self
+            .members
+            .iter()
+            .filter(move |m| m.rank_ordinal == Some(ordinal));
+    }
+
+    /// The members that are currently logged in
+    pub fn online(&self) -> impl Iterator<Item = &OutfitMember> {
+        return self.members.iter().filter(|m| m.is_online());
+    }
+
+    pub fn ids(&self) -> Vec<CharacterId> {
+        return self.members.iter().map(|m| m.character_id).collect();
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &OutfitMember> {
+        return self.members.iter();
+    }
+
+    pub fn len(&self) -> usize {
+        return self.members.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.members.is_empty();
+    }
+
+    /// Fetches every member as a full character, batched like Character::fetch_many
+    pub async fn fetch_characters(
+        &self,
+        rest_client: Arc<RestClient>,
+        resolves: Option<Vec<CharacterResolves>>,
+    ) -> Result<Vec<Character>, CensusError> {
+        return Character::fetch_many(rest_client, &self.ids(), resolves).await;
+    }
+}
+
+///
+/// Pages through the members of an outfit from outfit_member, oldest members first
+///
+/// The outfit resolves return every member in one response, which gets slow for large outfits.
+/// The pager joins the same character and online status details onto each page instead.
+///
+#[derive(Clone, Debug)]
+pub struct OutfitRosterPager {
+    outfit_id: OutfitId,
+    start: u64,
+    page_size: u64,
+    exhausted: bool,
+}
+
+impl OutfitRosterPager {
+    pub fn new(outfit_id: OutfitId) -> OutfitRosterPager {
+        return OutfitRosterPager {
+            outfit_id,
+            start: 0,
+            page_size: 500,
+            exhausted: false,
+        };
+    }
+
+    /// Census returns at most 5000 members per page, at least one member is requested
+    pub fn page_size(&mut self, page_size: u64) {
+        self.page_size = page_size.clamp(1, 5000);
+    }
+
+    pub fn is_exhausted(&self) -> bool {
+        return self.exhausted;
+    }
+
+    /// Fetches the next page of members, an empty page once every member was returned
+    pub async fn next_page(
+        &mut self,
+        rest_client: &RestClient,
+    ) -> Result<Vec<OutfitMember>, CensusError> {
+        if self.exhausted {
+            return Ok(Vec::new());
+        }
+
+        let mut query = rest_client.get_query_builder("outfit_member");
+
+        query.search("outfit_id".to_string(), self.outfit_id.to_string());
+
+        let mut character = Join::new(Character::COLLECTION);
+        character.on(Character::ID_FIELD);
+        character.inject_at("character");
+        character.show(vec![Character::ID_FIELD, "name", "battle_rank"]);
+        query.join(character);
+
+        let mut online_status = Join::new("characters_online_status");
+        online_status.on(Character::ID_FIELD);
+        online_status.inject_at("online");
+        query.join(online_status);
+
+        query.sort("member_since", true);
+        query.start(self.start);
+        query.limit(self.page_size);
+
+        let jsonval = query.get().await?;
+
+        let mut members = Vec::new();
+
+        if let Some(list) = jsonval["outfit_member_list"].as_array() {
+            if (list.len() as u64) < self.page_size {
+                self.exhausted = true;
+            }
+
+            for json in list {
+                rest_client.characters.merge_existing(&json["character"]);
+                rest_client.characters.merge_existing(&json["online"]);
+
+                if let Some(member) = OutfitMember::from_json(
+                    json,
+                    &json["character"],
+                    &json["online"]["online_status"],
+                ) {
+                    members.push(member);
+                }
+            }
+        } else {
+            self.exhausted = true;
+        }
+
+        self.start += self.page_size;
+
+        return Ok(members);
+    }
+
+    /// Fetches every remaining page into a roster, ranks are not part of outfit_member and are left empty
+    pub async fn collect(&mut self, rest_client: &RestClient) -> Result<OutfitRoster, CensusError> {
+        let mut roster = OutfitRoster::default();
+
+        while !self.exhausted {
+            roster.members.extend(self.next_page(rest_client).await?);
+        }
+
+        return Ok(roster);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::OutfitRosterPager;
+    use crate::shared::ids::OutfitId;
+
+    #[test]
+    fn page_size_is_clamped() {
+        let mut pager = OutfitRosterPager::new(OutfitId::new(37509488620604883));
+
+        pager.page_size(0);
+        assert_eq!(pager.page_size, 1);
+
+        pager.page_size(10000);
+        assert_eq!(pager.page_size, 5000);
+    }
+}
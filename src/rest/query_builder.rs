@@ -1,6 +1,9 @@
+use std::io::{self, Read};
 use std::sync::Arc;
 
+use serde::de::DeserializeOwned;
 use serde_json::Value;
+use tokio::sync::mpsc;
 
 use crate::utils::CensusError;
 
@@ -11,6 +14,134 @@ pub trait Resolveable {
     fn to_resolve_string(&self) -> String;
 }
 
+///
+/// A c:join of another collection onto the results of a query
+///
+#[derive(Clone, Debug)]
+pub struct Join {
+    collection: String,
+    on: Option<String>,
+    to: Option<String>,
+    list: bool,
+    inject_at: Option<String>,
+    terms: Vec<(String, String)>,
+    outer: bool,
+    show: Vec<String>,
+    hide: Vec<String>,
+    nested: Vec<Join>,
+}
+
+impl Join {
+    pub fn new(collection: &str) -> Join {
+        return Join {
+            collection: collection.to_string(),
+            on: None,
+            to: None,
+            list: false,
+            inject_at: None,
+            terms: Vec::new(),
+            outer: true,
+            show: Vec::new(),
+            hide: Vec::new(),
+            nested: Vec::new(),
+        };
+    }
+
+    /// The field of the parent to join on
+    pub fn on(&mut self, field: &str) {
+        self.on = Some(field.to_string());
+    }
+
+    /// The field of the joined collection to match, defaults to the on field
+    pub fn to(&mut self, field: &str) {
+        self.to = Some(field.to_string());
+    }
+
+    /// Injects every match as a list instead of only the first one
+    pub fn list(&mut self, list: bool) {
+        self.list = list;
+    }
+
+    pub fn inject_at(&mut self, field: &str) {
+        self.inject_at = Some(field.to_string());
+    }
+
+    /// Only joins rows matching the given value
+    pub fn term(&mut self, field: &str, value: &str) {
+        self.terms.push((field.to_string(), value.to_string()));
+    }
+
+    /// If false, parent rows without a match are removed from the results
+    pub fn outer(&mut self, outer: bool) {
+        self.outer = outer;
+    }
+
+    pub fn show(&mut self, fields: Vec<&str>) {
+        for i in fields {
+            self.show.push(i.to_string());
+        }
+    }
+
+    pub fn hide(&mut self, fields: Vec<&str>) {
+        for i in fields {
+            self.hide.push(i.to_string());
+        }
+    }
+
+    /// Joins another collection onto the rows of this join
+    pub fn join(&mut self, join: Join) {
+        self.nested.push(join);
+    }
+
+    fn to_command(&self) -> String {
+        let mut cmd = self.collection.clone();
+
+        if let Some(on) = &self.on {
+            cmd += &("^on:".to_string() + on);
+        }
+
+        if let Some(to) = &self.to {
+            cmd += &("^to:".to_string() + to);
+        }
+
+        if self.list {
+            cmd += "^list:1";
+        }
+
+        if let Some(inject_at) = &self.inject_at {
+            cmd += &("^inject_at:".to_string() + inject_at);
+        }
+
+        if !self.terms.is_empty() {
+            let terms: Vec<String> = self
+                .terms
+                .iter()
+                .map(|(field, value)| field.clone() + "=" + value)
+                .collect();
+            cmd += &("^terms:".to_string() + &terms.join("'"));
+        }
+
+        if !self.outer {
+            cmd += "^outer:0";
+        }
+
+        if !self.show.is_empty() {
+            cmd += &("^show:".to_string() + &self.show.join("'"));
+        }
+
+        if !self.hide.is_empty() {
+            cmd += &("^hide:".to_string() + &self.hide.join("'"));
+        }
+
+        if !self.nested.is_empty() {
+            let nested: Vec<String> = self.nested.iter().map(|j| j.to_command()).collect();
+            cmd += &("(".to_string() + &nested.join(",") + ")");
+        }
+
+        return cmd;
+    }
+}
+
 #[derive(Clone)]
 pub struct QueryBuilder {
     collection: String,
@@ -18,11 +149,15 @@ pub struct QueryBuilder {
     endpoint: String,
     pub reqwest_client: Arc<reqwest::Client>,
     resolves_vec: Vec<String>,
+    show_vec: Vec<String>,
+    sort_vec: Vec<String>,
+    join_vec: Vec<Join>,
     search_vec: Vec<(String, String)>,
     limit: u64,
     start: u64,
     lang: String,
     should_retry: bool,
+    exact_match_first: bool,
 }
 
 impl QueryBuilder {
@@ -38,11 +173,15 @@ impl QueryBuilder {
             endpoint,
             reqwest_client,
             resolves_vec: Vec::new(),
+            show_vec: Vec::new(),
+            sort_vec: Vec::new(),
+            join_vec: Vec::new(),
             search_vec: Vec::new(),
             limit: 0,
-            lang: crate::rest::api::langs::ENGLISH.to_string(),
+            lang: String::new(),
             start: 0,
             should_retry: false,
+            exact_match_first: false,
         };
     }
 
@@ -56,6 +195,31 @@ impl QueryBuilder {
         }
     }
 
+    /// Limits the returned fields to the given top level fields
+    pub fn show(&mut self, fields: Vec<&str>) {
+        for i in fields {
+            self.show_vec.push(i.to_string());
+        }
+    }
+
+    /// Sorts the results by a field, later calls are used to break ties
+    pub fn sort(&mut self, field: &str, ascending: bool) {
+        if ascending {
+            self.sort_vec.push(field.to_string() + ":1");
+        } else {
+            self.sort_vec.push(field.to_string() + ":-1");
+        }
+    }
+
+    pub fn join(&mut self, join: Join) {
+        self.join_vec.push(join);
+    }
+
+    /// Puts exact matches of a starts with or contains search first
+    pub fn exact_match_first(&mut self, exact_match_first: bool) {
+        self.exact_match_first = exact_match_first;
+    }
+
     pub fn limit(&mut self, limit: u64) {
         self.limit = limit;
     }
@@ -64,6 +228,7 @@ impl QueryBuilder {
         self.start = start;
     }
 
+    /// Limits localised strings to a single language, census returns every language unless one is set
     pub fn lang(&mut self, lang: &str) {
         self.lang = lang.to_string();
     }
@@ -80,10 +245,27 @@ impl QueryBuilder {
     fn parse_commands(&self) -> Vec<(String, String)> {
         let mut cmd: Vec<(String, String)> = Vec::with_capacity(5);
 
-        if self.resolves_vec.len() > 0 {
+        if !self.resolves_vec.is_empty() {
             cmd.push(("c:resolve".to_string(), self.resolves_vec.join(",")));
         }
 
+        if !self.show_vec.is_empty() {
+            cmd.push(("c:show".to_string(), self.show_vec.join(",")));
+        }
+
+        if !self.join_vec.is_empty() {
+            let joins: Vec<String> = self.join_vec.iter().map(|j| j.to_command()).collect();
+            cmd.push(("c:join".to_string(), joins.join(",")));
+        }
+
+        if self.exact_match_first {
+            cmd.push(("c:exactMatchFirst".to_string(), "true".to_string()));
+        }
+
+        if !self.sort_vec.is_empty() {
+            cmd.push(("c:sort".to_string(), self.sort_vec.join(",")));
+        }
+
         if self.limit > 0 {
             cmd.push(("c:limit".to_string(), self.limit.to_string()));
         }
@@ -92,7 +274,7 @@ impl QueryBuilder {
             cmd.push(("c:start".to_string(), self.start.to_string()));
         }
 
-        if self.lang != "" {
+        if !self.lang.is_empty() {
             cmd.push(("c:lang".to_string(), self.lang.clone()));
         }
 
@@ -159,4 +341,263 @@ impl QueryBuilder {
 
         return Ok(res_wrapped.unwrap());
     }
+
+    /// Deserializes the response while it is received, for responses too large to hold twice
+    ///
+    /// The body chunks are parsed on a blocking thread as they arrive, so neither the body
+    /// nor a String of it is ever held as a whole, only the deserialized value is.
+    pub async fn get_streamed<T: DeserializeOwned + Send + 'static>(
+        &self,
+    ) -> Result<T, CensusError> {
+        let req = self.build_query("get");
+
+        let mut response = match req.send().await {
+            Ok(response) => response,
+            Err(err) => {
+                return Err(CensusError {
+                    err_msg: err.to_string(),
+                    parent_err: None,
+                });
+            }
+        };
+
+        let status = response.status();
+
+        if !status.is_success() {
+            return Err(CensusError {
+                err_msg: "Census responded with status ".to_string() + status.as_str(),
+                parent_err: None,
+            });
+        }
+
+        let (sender, receiver) = mpsc::channel(STREAM_CHUNK_BUFFER);
+
+        let parser = tokio::task::spawn_blocking(move || {
+            serde_json::from_reader(ChunkReader::new(receiver))
+        });
+
+        loop {
+            match response.chunk().await {
+                Ok(Some(chunk)) => {
+                    // the parser stopped early, its error is returned below
+                    if sender.send(chunk).await.is_err() {
+                        break;
+                    }
+                }
+                Ok(None) => {
+                    break;
+                }
+                Err(err) => {
+                    return Err(CensusError {
+                        err_msg: err.to_string(),
+                        parent_err: None,
+                    });
+                }
+            }
+        }
+
+        drop(sender);
+
+        match parser.await {
+            Ok(Ok(value)) => {
+                return Ok(value);
+            }
+            Ok(Err(err)) => {
+                return Err(CensusError {
+                    err_msg: "Could not parse the census response".to_string(),
+                    parent_err: Some(err.to_string()),
+                });
+            }
+            Err(err) => {
+                return Err(CensusError {
+                    err_msg: "The census response parser stopped".to_string(),
+                    parent_err: Some(err.to_string()),
+                });
+            }
+        }
+    }
+}
+
+/// How many received body chunks may wait for the parser before receiving pauses
+const STREAM_CHUNK_BUFFER: usize = 16;
+
+///
+/// Reads body chunks from a channel as they arrive, the end of the body is reached once the sender is dropped
+///
+/// Blocks while waiting for a chunk and must only be read outside of the async runtime.
+///
+struct ChunkReader<B> {
+    receiver: mpsc::Receiver<B>,
+    chunk: Option<B>,
+    offset: usize,
+}
+
+impl<B: AsRef<[u8]>> ChunkReader<B> {
+    fn new(receiver: mpsc::Receiver<B>) -> ChunkReader<B> {
+        return ChunkReader {
+            receiver,
+            chunk: None,
+            offset: 0,
+        };
+    }
+}
+
+impl<B: AsRef<[u8]>> Read for ChunkReader<B> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            if let Some(chunk) = &self.chunk {
+                let rest = &chunk.as_ref()[self.offset..];
+
+                if !rest.is_empty() {
+                    let len = rest.len().min(buf.len());
+                    buf[..len].copy_from_slice(&rest[..len]);
+                    self.offset += len;
+
+                    return Ok(len);
+                }
+            }
+
+            match self.receiver.blocking_recv() {
+                Some(chunk) => {
+                    self.chunk = Some(chunk);
+                    self.offset = 0;
+                }
+                None => {
+                    return Ok(0);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use serde_json::{json, Value};
+    use tokio::sync::mpsc;
+
+    use super::{ChunkReader, Join, QueryBuilder};
+
+    fn join_command(query: &QueryBuilder) -> Option<String> {
+        return query
+            .parse_commands()
+            .into_iter()
+            .find(|(key, _)| key == "c:join")
+            .map(|(_, value)| value);
+    }
+
+    fn query() -> QueryBuilder {
+        return QueryBuilder::new(
+            "example".to_string(),
+            "ps2:v2".to_string(),
+            Arc::new(reqwest::Client::new()),
+            "character".to_string(),
+        );
+    }
+
+    #[test]
+    fn show_join() {
+        let mut world = Join::new("characters_world");
+        world.on("character_id");
+        world.inject_at("world");
+        world.show(vec!["world_id"]);
+
+        let mut query = query();
+        query.join(world);
+
+        assert_eq!(
+            join_command(&query).as_deref(),
+            Some("characters_world^on:character_id^inject_at:world^show:world_id")
+        );
+    }
+
+    #[test]
+    fn terms_join() {
+        let mut stats = Join::new("characters_weapon_stat");
+        stats.on("character_id");
+        stats.list(true);
+        stats.term("item_id", "80");
+        stats.term("vehicle_id", "0");
+        stats.outer(false);
+        stats.show(vec!["stat_name", "value"]);
+        stats.hide(vec!["last_save"]);
+
+        let mut query = query();
+        query.join(stats);
+
+        assert_eq!(
+            join_command(&query).as_deref(),
+            Some(
+                "characters_weapon_stat^on:character_id^list:1^terms:item_id=80'vehicle_id=0\
+                 ^outer:0^show:stat_name'value^hide:last_save"
+            )
+        );
+    }
+
+    #[test]
+    fn nested_joins() {
+        let mut item = Join::new("item");
+        item.on("item_id");
+        item.inject_at("item");
+
+        let mut category = Join::new("item_category");
+        category.on("item_category_id");
+        category.to("item_category_id");
+        category.inject_at("category");
+        item.join(category);
+
+        let mut attachments = Join::new("item_attachment");
+        attachments.on("item_id");
+        attachments.list(true);
+        attachments.inject_at("attachments");
+        item.join(attachments);
+
+        let mut online = Join::new("characters_online_status");
+        online.on("character_id");
+        online.inject_at("online");
+
+        let mut query = query();
+        query.join(item);
+        query.join(online);
+
+        assert_eq!(
+            join_command(&query).as_deref(),
+            Some(
+                "item^on:item_id^inject_at:item(\
+                 item_category^on:item_category_id^to:item_category_id^inject_at:category,\
+                 item_attachment^on:item_id^list:1^inject_at:attachments),\
+                 characters_online_status^on:character_id^inject_at:online"
+            )
+        );
+    }
+
+    #[test]
+    fn no_join() {
+        assert_eq!(join_command(&query()), None);
+    }
+
+    #[tokio::test]
+    async fn chunks_are_parsed_as_they_arrive() {
+        let (sender, receiver) = mpsc::channel::<Vec<u8>>(1);
+
+        let parser = tokio::task::spawn_blocking(move || {
+            serde_json::from_reader::<_, Value>(ChunkReader::new(receiver))
+        });
+
+        for chunk in [
+            "{\"character_list\":[{\"name\"",
+            "",
+            ":\"Exam",
+            "ple\"}],\"returned\":1}",
+        ] {
+            sender.send(chunk.as_bytes().to_vec()).await.unwrap();
+        }
+        drop(sender);
+
+        assert_eq!(
+            parser.await.unwrap().unwrap(),
+            json!({ "character_list": [{ "name": "Example" }], "returned": 1 })
+        );
+    }
 }
@@ -0,0 +1,319 @@
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    str::FromStr,
+    sync::{Arc, Mutex, Weak},
+};
+
+use serde_json::Value;
+use tokio::sync::RwLock;
+
+use crate::utils::CensusError;
+
+use super::RestClient;
+
+/// Registries are swept for unused entries once they grow past this many entries
+const MIN_SWEEP_SIZE: usize = 64;
+
+///
+/// An entity that can be shared through an EntityRegistry, implemented by the CensusEntity derive
+///
+pub trait RegistryEntity: Sized {
+    type Id: Clone + Eq + Hash + FromStr;
+    type Change;
+
+    const COLLECTION: &'static str;
+    const ID_FIELD: &'static str;
+
+    fn entity_id(&self) -> &Self::Id;
+    fn new_entity(id: Self::Id, rest_client: Arc<RestClient>) -> Self;
+    fn update_entity(&mut self, json: &Value) -> Vec<Self::Change>;
+}
+
+#[derive(Debug)]
+struct RegistryEntries<K, T> {
+    entries: HashMap<K, Weak<RwLock<T>>>,
+    sweep_at: usize,
+}
+
+///
+/// Hands out one shared instance per entity id
+///
+/// The registry only holds weak references, an entity is dropped as soon as the last handle to it is dropped
+/// and its entry is evicted on the next sweep.
+///
+#[derive(Debug)]
+pub struct EntityRegistry<T: RegistryEntity> {
+    inner: Mutex<RegistryEntries<T::Id, T>>,
+}
+
+impl<T: RegistryEntity> Default for EntityRegistry<T> {
+    fn default() -> Self {
+        return EntityRegistry::new();
+    }
+}
+
+impl<T: RegistryEntity> EntityRegistry<T> {
+    pub fn new() -> Self {
+        return EntityRegistry {
+            inner: Mutex::new(RegistryEntries {
+                entries: HashMap::new(),
+                sweep_at: MIN_SWEEP_SIZE,
+            }),
+        };
+    }
+
+    /// Returns the shared instance of an entity if anything is still holding it
+    pub fn get(&self, id: &T::Id) -> Option<Arc<RwLock<T>>> {
+        let inner = self.inner.lock().unwrap();
+
+        return inner.entries.get(id).and_then(|weak| weak.upgrade());
+    }
+
+    /// Returns the shared instance of an entity, creating an empty one if there is none
+    pub fn get_or_create(&self, id: T::Id, rest_client: Arc<RestClient>) -> Arc<RwLock<T>> {
+        let mut inner = self.inner.lock().unwrap();
+
+        if let Some(entity) = inner.entries.get(&id).and_then(|weak| weak.upgrade()) {
+            return entity;
+        }
+
+        let entity = Arc::new(RwLock::new(T::new_entity(id.clone(), rest_client)));
+
+        inner.entries.insert(id, Arc::downgrade(&entity));
+
+        if inner.entries.len() >= inner.sweep_at {
+            inner.entries.retain(|_, weak| weak.strong_count() > 0);
+            inner.sweep_at = MIN_SWEEP_SIZE.max(inner.entries.len() * 2);
+        }
+
+        return entity;
+    }
+
+    /// Applies a fetched json object to the shared instance of the entity it describes
+    ///
+    /// Waits for the write lock of the instance, so it must not be awaited while holding a lock on it.
+    pub async fn merge(
+        &self,
+        json: &Value,
+        rest_client: Arc<RestClient>,
+    ) -> Result<(Arc<RwLock<T>>, Vec<T::Change>), CensusError> {
+        let id = parse_entity_id::<T>(json)?;
+
+        let entity = self.get_or_create(id, rest_client);
+
+        let changes = entity.write().await.update_entity(json);
+
+        return Ok((entity, changes));
+    }
+
+    /// Applies a fetched json object to the shared instance of the entity it describes,
+    /// if anything is still holding one, returning the fields that changed
+    ///
+    /// Never waits for a lock: an instance that is locked, for example because it is the instance
+    /// the json was fetched for, is left as is and None is returned.
+    pub fn merge_existing(&self, json: &Value) -> Option<Vec<T::Change>> {
+        let id = parse_entity_id::<T>(json).ok()?;
+
+        let entity = self.get(&id)?;
+
+        let mut guard = entity.try_write().ok()?;
+
+        return Some(guard.update_entity(json));
+    }
+
+    /// Removes the entries of entities that are no longer held anywhere, returning how many were removed
+    pub fn evict_unused(&self) -> usize {
+        let mut inner = self.inner.lock().unwrap();
+
+        let before = inner.entries.len();
+        inner.entries.retain(|_, weak| weak.strong_count() > 0);
+        inner.sweep_at = MIN_SWEEP_SIZE.max(inner.entries.len() * 2);
+
+        return before - inner.entries.len();
+    }
+
+    /// The number of entries, including ones that have not been evicted yet
+    pub fn len(&self) -> usize {
+        return self.inner.lock().unwrap().entries.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.len() == 0;
+    }
+}
+
+/// The id of an entity json object, an error if it has none
+pub(crate) fn parse_entity_id<T: RegistryEntity>(json: &Value) -> Result<T::Id, CensusError> {
+    match json[T::ID_FIELD].as_str().map(|s| s.parse::<T::Id>()) {
+        Some(Ok(id)) => {
+            return Ok(id);
+        }
+        _ => {
+            return Err(CensusError {
+                err_msg: "Could not get ".to_string() + T::ID_FIELD,
+                parent_err: None,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use serde_json::json;
+
+    use super::{EntityRegistry, MIN_SWEEP_SIZE};
+    use crate::rest::census_value::ValueChange;
+    use crate::rest::character::{Character, CharacterChange};
+    use crate::rest::RestClient;
+    use crate::shared::ids::CharacterId;
+
+    fn rest_client() -> Arc<RestClient> {
+        return Arc::new(RestClient::new("example".to_string()));
+    }
+
+    fn character_json(id: u64, battle_rank: u8) -> serde_json::Value {
+        return json!({
+            "character_id": id.to_string(),
+            "battle_rank": { "value": battle_rank.to_string() },
+        });
+    }
+
+    #[test]
+    fn from_json_value_needs_an_id() {
+        let character = Character::from_json_value(&character_json(1, 20), rest_client()).unwrap();
+
+        assert_eq!(character.id, CharacterId::new(1));
+        assert_eq!(character.battle_rank.value, Some(20));
+
+        let err =
+            Character::from_json_value(&json!({ "battle_rank": { "value": "20" } }), rest_client())
+                .unwrap_err();
+
+        assert_eq!(err.err_msg, "Could not get character_id");
+    }
+
+    #[test]
+    fn get_or_create_shares_instances() {
+        let registry: EntityRegistry<Character> = EntityRegistry::new();
+        let rest_client = rest_client();
+
+        let first = registry.get_or_create(CharacterId::new(1), rest_client.clone());
+        let second = registry.get_or_create(CharacterId::new(1), rest_client.clone());
+        let other = registry.get_or_create(CharacterId::new(2), rest_client);
+
+        assert!(Arc::ptr_eq(&first, &second));
+        assert!(!Arc::ptr_eq(&first, &other));
+        assert!(Arc::ptr_eq(
+            &registry.get(&CharacterId::new(1)).unwrap(),
+            &first
+        ));
+        assert_eq!(registry.len(), 2);
+    }
+
+    #[test]
+    fn dropped_instances_are_evicted() {
+        let registry: EntityRegistry<Character> = EntityRegistry::new();
+
+        let held = registry.get_or_create(CharacterId::new(1), rest_client());
+        drop(registry.get_or_create(CharacterId::new(2), rest_client()));
+
+        assert!(registry.get(&CharacterId::new(2)).is_none());
+        assert_eq!(registry.len(), 2);
+        assert_eq!(registry.evict_unused(), 1);
+        assert_eq!(registry.len(), 1);
+        assert!(registry.get(&CharacterId::new(1)).is_some());
+
+        drop(held);
+
+        assert_eq!(registry.evict_unused(), 1);
+        assert!(registry.is_empty());
+    }
+
+    #[test]
+    fn sweeps_grow_with_held_entries() {
+        let registry: EntityRegistry<Character> = EntityRegistry::new();
+        let rest_client = rest_client();
+
+        // dropped entries are swept once the registry reaches the sweep size,
+        // only the entry created by the call that triggered the sweep is kept
+        for id in 0..MIN_SWEEP_SIZE as u64 {
+            drop(registry.get_or_create(CharacterId::new(id), rest_client.clone()));
+        }
+
+        assert_eq!(registry.len(), 1);
+        assert_eq!(registry.inner.lock().unwrap().sweep_at, MIN_SWEEP_SIZE);
+
+        // held entries survive the sweep and push the next one to twice their count
+        let held: Vec<_> = (0..MIN_SWEEP_SIZE as u64)
+            .map(|id| registry.get_or_create(CharacterId::new(id), rest_client.clone()))
+            .collect();
+
+        assert_eq!(registry.len(), MIN_SWEEP_SIZE);
+        assert_eq!(
+            registry.inner.lock().unwrap().sweep_at,
+            (MIN_SWEEP_SIZE - 1) * 2
+        );
+
+        for id in MIN_SWEEP_SIZE as u64..(MIN_SWEEP_SIZE as u64 * 2) {
+            drop(registry.get_or_create(CharacterId::new(id), rest_client.clone()));
+        }
+
+        let len = registry.len();
+
+        assert!(len <= MIN_SWEEP_SIZE * 2);
+        assert_eq!(registry.evict_unused(), len - MIN_SWEEP_SIZE);
+
+        drop(held);
+    }
+
+    #[tokio::test]
+    async fn merge_existing() {
+        let registry: EntityRegistry<Character> = EntityRegistry::new();
+
+        assert!(registry.merge_existing(&character_json(1, 20)).is_none());
+        assert!(registry
+            .merge_existing(&json!({ "name": "no id" }))
+            .is_none());
+        assert!(registry.is_empty());
+
+        let character = registry.get_or_create(CharacterId::new(1), rest_client());
+
+        assert_eq!(
+            registry.merge_existing(&character_json(1, 20)),
+            Some(vec![CharacterChange::BattleRank(ValueChange {
+                previous: None,
+                current: 20,
+            })])
+        );
+        assert_eq!(character.read().await.battle_rank.value, Some(20));
+
+        // a locked instance is skipped instead of waited for
+        let guard = character.read().await;
+
+        assert!(registry.merge_existing(&character_json(1, 21)).is_none());
+        assert_eq!(guard.battle_rank.value, Some(20));
+    }
+
+    #[tokio::test]
+    async fn merge_creates_instances() {
+        let registry: EntityRegistry<Character> = EntityRegistry::new();
+
+        let (character, changes) = registry
+            .merge(&character_json(1, 20), rest_client())
+            .await
+            .unwrap();
+
+        assert_eq!(changes.len(), 1);
+        assert!(Arc::ptr_eq(
+            &registry.get(&CharacterId::new(1)).unwrap(),
+            &character
+        ));
+        assert!(registry
+            .merge(&json!({ "name": "no id" }), rest_client())
+            .await
+            .is_err());
+    }
+}
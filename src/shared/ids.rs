@@ -0,0 +1,95 @@
+use std::{fmt, num::ParseIntError, str::FromStr};
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+macro_rules! census_id {
+    ($(#[$doc:meta])* $name:ident, $inner:ty) => {
+        $(#[$doc])*
+        #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub struct $name(pub $inner);
+
+        impl $name {
+            pub fn new(id: $inner) -> Self {
+                return $name(id);
+            }
+
+            pub fn value(&self) -> $inner {
+                return self.0;
+            }
+        }
+
+        impl From<$inner> for $name {
+            fn from(id: $inner) -> Self {
+                return $name(id);
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = ParseIntError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                return Ok($name(s.parse::<$inner>()?));
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "{}", self.0)
+            }
+        }
+
+        // census sends every id as a string, so they are serialized as one too
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                return serializer.collect_str(self);
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                #[derive(Deserialize)]
+                #[serde(untagged)]
+                enum RawId {
+                    Str(String),
+                    Num($inner),
+                }
+
+                match RawId::deserialize(deserializer)? {
+                    RawId::Str(s) => {
+                        return s.parse::<$name>().map_err(de::Error::custom);
+                    }
+                    RawId::Num(n) => {
+                        return Ok($name(n));
+                    }
+                }
+            }
+        }
+    };
+}
+
+census_id!(CharacterId, u64);
+census_id!(OutfitId, u64);
+census_id!(ItemId, u64);
+census_id!(AchievementId, u64);
+census_id!(WorldId, u16);
+census_id!(
+    /// Census encodes the zone instance in the upper 16 bits of the zone id
+    ZoneId,
+    u32
+);
+census_id!(FacilityId, u64);
+census_id!(ExperienceId, u64);
+census_id!(VehicleId, u64);
+census_id!(LoadoutId, u64);
+
+impl ZoneId {
+    /// The id of the zone definition, the same for every instance of a continent
+    pub fn definition_id(&self) -> u16 {
+        return (self.0 & 0xFFFF) as u16;
+    }
+
+    /// The instance of the zone, 0 for the permanent continents
+    pub fn instance_id(&self) -> u16 {
+        return (self.0 >> 16) as u16;
+    }
+}
@@ -1,4 +1,9 @@
 pub mod pc {
+    use serde::Serialize;
+
+    use super::World;
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize)]
     pub enum Worlds {
         COBALT,
         CONNERY,
@@ -10,6 +15,27 @@ pub mod pc {
         BRIGGS,
     }
 
+    impl Worlds {
+        pub const ALL: [Worlds; 8] = [
+            Worlds::COBALT,
+            Worlds::CONNERY,
+            Worlds::MILLER,
+            Worlds::JAEGER,
+            Worlds::EMERALD,
+            Worlds::SOLTECH,
+            Worlds::APEX,
+            Worlds::BRIGGS,
+        ];
+
+        /// The pc world with the given id, None for the worlds of other platforms like ps4
+        pub fn from_id(id: u16) -> Option<Worlds> {
+            return Worlds::ALL
+                .iter()
+                .find(|w| *w.id_u8() as u16 == id)
+                .copied();
+        }
+    }
+
     impl super::World for Worlds {
         fn id_u8(&self) -> &'static u8 {
             match self {
@@ -25,19 +51,24 @@ pub mod pc {
         }
     }
 
-    pub const COBALT_ID: &'static u8 = &13;
-    pub const CONNERY_ID: &'static u8 = &1;
-    pub const MILLER_ID: &'static u8 = &10;
-    pub const JAEGER_ID: &'static u8 = &19;
-    pub const EMERALD_ID: &'static u8 = &17;
-    pub const SOLTECH_ID: &'static u8 = &40;
-    pub const APEX_ID: &'static u8 = &24;
-    pub const BRIGGS_ID: &'static u8 = &25;
+    pub const COBALT_ID: &u8 = &13;
+    pub const CONNERY_ID: &u8 = &1;
+    pub const MILLER_ID: &u8 = &10;
+    pub const JAEGER_ID: &u8 = &19;
+    pub const EMERALD_ID: &u8 = &17;
+    pub const SOLTECH_ID: &u8 = &40;
+    pub const APEX_ID: &u8 = &24;
+    pub const BRIGGS_ID: &u8 = &25;
 }
 
+use super::ids::WorldId;
+
 pub trait World {
     fn id_string(&self) -> String {
         return self.id_u8().to_string();
     }
+    fn world_id(&self) -> WorldId {
+        return WorldId::new(*self.id_u8() as u16);
+    }
     fn id_u8(&self) -> &'static u8;
 }
@@ -1,4 +1,8 @@
 use std::fmt;
+use std::str::FromStr;
+
+use serde::Serialize;
+use serde_json::Value;
 
 #[derive(Debug, Clone)]
 pub struct CensusError {
@@ -22,3 +26,54 @@ impl fmt::Display for CensusError {
         }
     }
 }
+
+/// Divides two counters, returning the numerator if the denominator is 0 like the in-game stats do
+pub(crate) fn ratio(a: u64, b: u64) -> f64 {
+    if b == 0 {
+        return a as f64;
+    }
+
+    return a as f64 / b as f64;
+}
+
+/// The value per minute of the given amount of seconds, 0 if no time passed
+pub(crate) fn per_minute(value: u64, seconds: u64) -> f64 {
+    if seconds == 0 {
+        return 0_f64;
+    }
+
+    return value as f64 / (seconds as f64 / 60_f64);
+}
+
+/// Parses a value census sends as a string, like ids and numbers, None if it is missing or malformed
+pub(crate) fn parse_optional<T: FromStr>(json: &Value) -> Option<T> {
+    return json.as_str().and_then(|s| s.parse::<T>().ok());
+}
+
+/// Parses a census number string, 0 if it is missing or not a number
+pub(crate) fn parse_u64(json: &Value) -> u64 {
+    return parse_optional(json).unwrap_or(0);
+}
+
+/// Census sends 0 for unset timestamps and counters
+pub(crate) fn parse_nonzero_u64(json: &Value) -> Option<u64> {
+    match parse_u64(json) {
+        0 => None,
+        value => Some(value),
+    }
+}
+
+/// Serializes a value as pretty printed json, what names the value in the error
+pub(crate) fn to_json_pretty<T: Serialize>(value: &T, what: &str) -> Result<String, CensusError> {
+    match serde_json::to_string_pretty(value) {
+        Ok(json) => {
+            return Ok(json);
+        }
+        Err(err) => {
+            return Err(CensusError {
+                err_msg: "Could not serialize ".to_string() + what,
+                parent_err: Some(err.to_string()),
+            });
+        }
+    }
+}